@@ -30,8 +30,8 @@ function GeneratorFields() {
           <option value="2">Medium</option>
           <option value="4">Hard</option>
           <option value="5">(SLOW) Settis, small fishes</option>
-          <option value="6">(VERY SLOW) Large fishes, simple guesses</option>
-          <option value="7">(EXTREMELY SLOW) Complex guesses</option>
+          <option value="6">(VERY SLOW) Large fishes, chains, simple guesses</option>
+          <option value="7">(EXTREMELY SLOW) Pattern overlays, complex guesses</option>
         </select>
       </label>
       <label className="flex items-center py-2">
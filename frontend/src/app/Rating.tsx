@@ -40,13 +40,21 @@ export function Rating(props: RatingProps) {
     cross_compartment_ranges,
     maintain_reqs_and_blocks,
     sets,
+    hidden_sets,
     setti,
     y_wing,
+    xyz_wing,
     x_wing,
     swordfish,
     n_fish,
+    x_chain,
+    finned_fish,
+    xy_chain,
     medusa,
+    aic,
     unique_requirement,
+    pattern_overlay,
+    forcing_chain,
     short_guess_count,
     long_guess_count,
   } = difficulty;
@@ -76,17 +84,25 @@ export function Rating(props: RatingProps) {
         <span className={cross_compartment_ranges ? enabled_class : disabled_class}>
           Cross-compartment range checks
         </span>
-        <span className={sets ? enabled_class : disabled_class}>Naked and hidden sets</span>
+        <span className={sets ? enabled_class : disabled_class}>Naked sets</span>
         <span className={maintain_reqs_and_blocks ? enabled_hard_class : hidden_class}>
           Maintain lists of required and forbidden numbers
         </span>
+        <span className={hidden_sets ? enabled_hard_class : hidden_class}>Hidden sets</span>
         <span className={setti ? enabled_hard_class : hidden_class}>Setti</span>
         <span className={y_wing ? enabled_hard_class : hidden_class}>Y-wing</span>
+        <span className={xyz_wing ? enabled_hard_class : hidden_class}>XYZ-wing</span>
         <span className={x_wing ? enabled_hard_class : hidden_class}>X-wing</span>
         <span className={swordfish ? enabled_hard_class : hidden_class}>Swordfish</span>
         <span className={n_fish > 4 ? enabled_hard_class : hidden_class}>{n_fish}-fish</span>
+        <span className={x_chain ? enabled_very_hard_class : hidden_class}>X-chain</span>
+        <span className={finned_fish ? enabled_very_hard_class : hidden_class}>Finned fish</span>
+        <span className={xy_chain ? enabled_very_hard_class : hidden_class}>XY-chain</span>
         <span className={medusa ? enabled_very_hard_class : hidden_class}>3D Medusa</span>
+        <span className={aic ? enabled_very_hard_class : hidden_class}>Alternating inference chain</span>
         <span className={unique_requirement ? enabled_very_hard_class : hidden_class}>Unique requirement</span>
+        <span className={pattern_overlay ? enabled_very_hard_class : hidden_class}>Pattern overlay</span>
+        <span className={forcing_chain ? enabled_very_hard_class : hidden_class}>Forcing chain</span>
         <span className={short_guess_count > 0 ? enabled_very_hard_class : hidden_class}>
           Short guess x {short_guess_count}
         </span>
@@ -50,13 +50,16 @@ function borderForSolution(cell: WasmSolveType): string {
   )
     return "";
   if (cell === "Medusa") return "border-t-8 border-t-blue-800";
-  if (cell === "RequiredAndForbidden" || cell === "RowColBrute" || "Setti" in cell)
+  if (cell === "RequiredAndForbidden" || cell === "RowColBrute" || "Setti" in cell || "HiddenSets" in cell)
     return "border-t-8 border-t-blue-700";
-  if ("YWing" in cell || "Sets" in cell) return "border-t-8 border-t-blue-700";
+  if ("YWing" in cell || "XyzWing" in cell || "Sets" in cell) return "border-t-8 border-t-blue-700";
+  if ("XyChain" in cell || "Aic" in cell) return "border-t-8 border-t-blue-800";
   if ("Fish" in cell && (cell.Fish === 2 || cell.Fish === 3)) return "border-t-8 border-t-blue-700";
-  if ("Fish" in cell) return "border-t-8 border-t-blue-800";
+  if ("Fish" in cell || "FinnedFish" in cell) return "border-t-8 border-t-blue-800";
+  if ("XChain" in cell) return "border-t-8 border-t-blue-800";
   if ("SimpleUniqueRequirement" in cell) return "border-t-8 border-t-blue-800";
   if ("UniqueRequirement" in cell) return "border-t-8 border-t-blue-800";
+  if ("PatternOverlay" in cell || "ForcingChain" in cell) return "border-t-8 border-t-blue-800";
   if ("StartGuess" in cell || "GuessStep" in cell || "EndGuess" in cell) return "border-t-8 border-t-blue-800";
   unreachable(cell);
 }
@@ -1,5 +1,10 @@
 onmessage = function (message) {
   import("../../../solver_wasm/target/pkg-web/solver_wasm.js").then((wasm) => {
-    wasm.default().then(() => self.postMessage(wasm.generate(message.data)));
+    const { daily, ...input } = message.data;
+    wasm
+      .default()
+      .then(() =>
+        self.postMessage(daily ? wasm.daily(daily.date, daily.target_difficulty) : wasm.generate(input)),
+      );
   });
 };
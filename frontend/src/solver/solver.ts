@@ -10,6 +10,12 @@ import {
   solve as wasmSolve,
   puzzle_difficulty as wasmPuzzleDifficulty,
   encode as wasmEncode,
+  encode_format_1 as wasmEncodeFormat1,
+  share_url as wasmShareUrl,
+  set_locale as wasmSetLocale,
+  locales as wasmLocales,
+  set_notation as wasmSetNotation,
+  notations as wasmNotations,
   WasmSolveResult,
   WasmDifficulty,
   WasmValidationResult,
@@ -119,6 +125,34 @@ export function encode(grid: Grid): string {
   return wasmEncode(gridToWasm(grid));
 }
 
+/* Only the givens, in the str8ts.com format with a digit and a black flag for every cell */
+export function encodeFormat1(grid: Grid): string {
+  return wasmEncodeFormat1(gridToWasm(grid));
+}
+
+/* A link to the grid with the player's progress, defaulting to the public solver */
+export function shareUrl(grid: Grid, base?: string): string {
+  return wasmShareUrl(gridToWasm(grid), base);
+}
+
+/* Language of the step descriptions, such as "en", "fi" or "de" */
+export function setLocale(locale: string) {
+  wasmSetLocale(locale);
+}
+
+export function locales(): string[] {
+  return wasmLocales();
+}
+
+/* Cell coordinates in the step descriptions: "xy" for (x, y), "r1c1" or "a1" */
+export function setNotation(notation: string) {
+  wasmSetNotation(notation);
+}
+
+export function notations(): string[] {
+  return wasmNotations();
+}
+
 export function getColors(row: WasmResult<WasmSolveResult, WasmValidationResult>): number[][][] | null {
   const colors = "Err" in row ? row.Err.meta.colors : row.Ok.meta.colors;
   if (colors.length) {
@@ -40,6 +40,7 @@ export type WasmUrResult =
 
 export interface WasmSolveMetadata {
   colors: [Point, number][][];
+  lines: [boolean, number][][];
 }
 
 export type WasmSolveType =
@@ -50,12 +51,20 @@ export type WasmSolveType =
   | "RequiredRange"
   | { Sets: number }
   | "RequiredAndForbidden"
+  | { HiddenSets: [Point[], number[]] }
   | "RowColBrute"
   | { Setti: number[] }
   | { YWing: [Point, number] }
+  | { XyzWing: [Point, [Point, number][]] }
   | { Fish: number }
+  | { XChain: [number, Point[]] }
+  | { FinnedFish: [number, boolean, number, Point[]] }
+  | { XyChain: [Point[], [Point, number][]] }
   | "Medusa"
+  | { Aic: [[Point, number][], [Point, number][]] }
   | { UniqueRequirement: WasmUrResult }
+  | { PatternOverlay: [number, Point[], Point[]] }
+  | { ForcingChain: [[Point, number][], [Point, number][], [Point, number][]] }
   | { StartGuess: [Point, number] }
   | { GuessStep: [Point, number, [WasmGrid, WasmSolveResult, string][], WasmGrid] }
   | { EndGuess: WasmValidationResult }
@@ -82,7 +91,7 @@ export type WasmValidationError =
   | { RequirementBlockerConflict: { vertical: boolean; index: number; number: number } }
   | { RequiredNumberMissing: { vertical: boolean; index: number; number: number } }
   | { BlockedNumberPresent: { vertical: boolean; index: number; number: number } }
-  | { Ambiguous: { cells: Point[] } }
+  | { Ambiguous: { cells: Point[]; solutions: number[][] } }
   | "OutOfStrats";
 
 export interface WasmValidationResult {
@@ -98,13 +107,21 @@ export interface WasmDifficulty {
   cross_compartment_ranges: boolean;
   maintain_reqs_and_blocks: boolean;
   sets: boolean;
+  hidden_sets: boolean;
   setti: boolean;
   y_wing: boolean;
+  xyz_wing: boolean;
   x_wing: boolean;
   swordfish: boolean;
   n_fish: number;
+  x_chain: boolean;
+  finned_fish: boolean;
+  xy_chain: boolean;
   medusa: boolean;
+  aic: boolean;
   unique_requirement: boolean;
+  pattern_overlay: boolean;
+  forcing_chain: boolean;
   short_guess_count: number;
   long_guess_count: number;
 }
@@ -148,6 +165,30 @@ export function encode(grid: WasmGrid): string {
   return mod.encode(grid);
 }
 
+export function encode_format_1(grid: WasmGrid): string {
+  return mod.encode_format_1(grid);
+}
+
+export function share_url(grid: WasmGrid, base?: string): string {
+  return mod.share_url(grid, base);
+}
+
+export function set_locale(locale: string) {
+  mod.set_locale(locale);
+}
+
+export function locales(): string[] {
+  return mod.locales();
+}
+
+export function set_notation(notation: string) {
+  mod.set_notation(notation);
+}
+
+export function notations(): string[] {
+  return mod.notations();
+}
+
 const generatorWorker = new Worker(new URL("generatorWorker.js", import.meta.url));
 generatorWorker.onerror = console.warn;
 
@@ -162,3 +203,16 @@ export async function generate(input: WasmGeneratorInput): Promise<{ grid: WasmG
     generatorWorker.postMessage(input);
   });
 }
+
+/* The puzzle of the day for a date like "2024-01-31", the same in every instance of the app */
+export async function daily(date: string, targetDifficulty: number): Promise<{ grid: WasmGrid; grid_str: string }> {
+  return new Promise<{ grid: WasmGrid; grid_str: string }>((resolve) => {
+    generatorWorker.onmessage = (ev) => {
+      resolve(ev.data);
+    };
+    generatorWorker.onerror = (e) => {
+      throw e;
+    };
+    generatorWorker.postMessage({ daily: { date, target_difficulty: targetDifficulty } });
+  });
+}
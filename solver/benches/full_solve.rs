@@ -4,7 +4,7 @@ use solver::generator::generate_solved_grid;
 
 use solver::grid::Grid;
 use solver::solve_result::{SolveResults, SolveType};
-use solver::solver::{solve_round};
+use solver::solver::solve_round;
 
 fn full_solve(mut grid: Grid, enable_chains: bool) -> usize {
     let orig_grid = grid.clone();
@@ -0,0 +1,69 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use solver::bitset::BitSet;
+use solver::grid::{Cell, Compartment};
+use solver::strats::{compartment_solutions, line_straights, solve_line, solve_line_cached};
+
+fn line(cells: &[&[u8]]) -> Vec<Compartment> {
+    let mut compartments = Vec::new();
+    let mut current = Vec::new();
+    for (x, set) in cells.iter().enumerate() {
+        if set.is_empty() {
+            compartments.push(Compartment {
+                cells: std::mem::take(&mut current),
+                vertical: false,
+            });
+        } else {
+            current.push(((x, 0), Cell::Indeterminate(set.iter().copied().collect())));
+        }
+    }
+    compartments.push(Compartment {
+        cells: current,
+        vertical: false,
+    });
+    compartments
+}
+
+fn line_solver_benchmark(c: &mut Criterion) {
+    let all: &[u8] = &[1, 2, 3, 4, 5, 6, 7, 8, 9];
+    let lines = [
+        (
+            "partial line",
+            line(&[
+                &[1, 2, 3, 4],
+                &[],
+                &[2, 3, 4, 5, 6, 8],
+                &[3, 4, 5, 7],
+                &[3, 4, 5, 6],
+                &[2, 3, 4, 5, 6],
+                &[],
+                &[7, 8, 9],
+                &[6, 8, 9],
+            ]),
+        ),
+        ("open line", line(&[all, all, all, &[], all, all, all, all, all])),
+    ];
+    let straights = line_straights(9);
+
+    for (name, compartments) in lines {
+        let cells = compartments
+            .iter()
+            .map(|compartment| compartment.cells.iter().map(|(_, cell)| cell.to_possibles()).collect())
+            .collect::<Vec<Vec<_>>>();
+        c.bench_function(&format!("{} compartment_solutions", name), |b| {
+            b.iter(|| compartment_solutions(black_box(&compartments), BitSet::new()))
+        });
+        c.bench_function(&format!("{} solve_line", name), |b| {
+            b.iter(|| solve_line(&straights, black_box(&cells), BitSet::new(), BitSet::new()))
+        });
+        c.bench_function(&format!("{} solve_line_cached", name), |b| {
+            b.iter(|| solve_line_cached(&straights, black_box(&cells), BitSet::new(), BitSet::new()))
+        });
+    }
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = line_solver_benchmark
+}
+criterion_main!(benches);
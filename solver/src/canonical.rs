@@ -0,0 +1,103 @@
+//! Canonical forms for detecting puzzles which are the same up to symmetry
+//!
+//! Transposing, mirroring or rotating the grid keeps every compartment intact, as does reversing the digits
+//! with n -> size + 1 - n. Together these give 16 variants of each puzzle, and the canonical form is the
+//! smallest encoding among them.
+
+use crate::grid::Grid;
+use crate::mistakes::original_puzzle;
+use crate::puzzle_coding::encode;
+
+/* The eight rotations and reflections of the puzzle, each with and without reversed digits */
+fn variants(grid: &Grid) -> Result<Vec<Grid>, String> {
+    let mut variants = Vec::new();
+    let mut current = original_puzzle(grid)?;
+    for _ in 0..4 {
+        let transposed = current.transpose();
+        variants.push(current.clone());
+        variants.push(transposed);
+        current = current.rotate(1);
+    }
+    let reversed = variants.iter().map(Grid::reverse_digits).collect::<Vec<_>>();
+    variants.extend(reversed);
+    Ok(variants)
+}
+
+/* Givens and black cells of the puzzle in its canonical orientation, in the str8ts.com format. Player
+ * progress is ignored, so a partially solved grid has the same canonical form as its puzzle. */
+pub fn canonical_form(grid: &Grid) -> Result<String, String> {
+    Ok(variants(grid)?.iter().map(encode).min().unwrap())
+}
+
+pub fn are_equivalent(a: &Grid, b: &Grid) -> Result<bool, String> {
+    Ok(a.x == b.x && a.y == b.y && canonical_form(a)? == canonical_form(b)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Cell::*;
+    use crate::utils::*;
+
+    const PUZZLE: &str = "
+12#.7...6
+.#.4..3..
+.#.#i....
+7..3.....
+9#3#.#8#7
+.....1..4
+....h#.#.
+..9..8.#.
+5...4.#32
+";
+
+    #[test]
+    fn test_variants() {
+        let grid = g(PUZZLE);
+        let variants = variants(&grid).unwrap();
+        assert_eq!(variants.len(), 16);
+        assert_eq!(variants[0], original_puzzle(&grid).unwrap());
+        assert_eq!(grid.mirror_horizontal().mirror_horizontal(), grid);
+        assert_eq!(grid.transpose().transpose(), grid);
+        assert_eq!(grid.reverse_digits().cells[0][0], Requirement(9));
+
+        /* the fourth rotation is the original grid again */
+        assert_eq!(grid.rotate(1).rotate(1).rotate(1).rotate(1), grid);
+        let canonical = canonical_form(&grid).unwrap();
+        for variant in &variants {
+            assert_eq!(canonical_form(variant), Ok(canonical.clone()));
+        }
+    }
+
+    #[test]
+    fn test_equivalence() {
+        let grid = g(PUZZLE);
+        let rotated_180 = PUZZLE
+            .trim()
+            .lines()
+            .rev()
+            .map(|line| line.chars().rev().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let rotated_180 = g(&rotated_180);
+        assert_eq!(are_equivalent(&grid, &rotated_180), Ok(true));
+        assert_eq!(are_equivalent(&grid, &rotated_180.reverse_digits()), Ok(true));
+
+        let mut progress = grid.clone();
+        progress.set_cell((3, 0), Solution(5));
+        assert_eq!(are_equivalent(&grid, &progress), Ok(true));
+
+        let mut different = grid.clone();
+        different.set_cell((3, 0), Requirement(5));
+        assert_eq!(are_equivalent(&grid, &different), Ok(false));
+        assert_eq!(are_equivalent(&grid, &g("..\n..")), Ok(false));
+    }
+
+    #[test]
+    fn test_malformed() {
+        let mut grid = g(PUZZLE);
+        grid.cells.pop();
+        assert!(canonical_form(&grid).is_err());
+        assert!(are_equivalent(&g(PUZZLE), &grid).is_err());
+    }
+}
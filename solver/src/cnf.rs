@@ -0,0 +1,328 @@
+//! SAT encoding of a puzzle in the DIMACS CNF format, for cross-checking with external SAT solvers
+//!
+//! Variable `cell_variable(size, (x, y), n)` is true when cell (x, y) holds n. After the cell variables, every
+//! compartment gets one variable per straight it could hold, telling the smallest number of the straight.
+//! The encoding has clauses for
+//!
+//! - cell domains: every white cell holds exactly one of its candidates, givens hold their number
+//! - rows and columns: no number appears twice, including numbers in black cells
+//! - compartments: exactly one straight is chosen, and the cells only hold numbers from it
+//! - the required and forbidden numbers found so far for each row and column
+//!
+//! A solver output is turned back to a `Grid` with `decode_assignment`.
+
+use crate::grid::Cell::*;
+use crate::grid::{Compartment, Grid, Point};
+use itertools::Itertools;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cnf {
+    pub variables: usize,
+    pub clauses: Vec<Vec<i32>>,
+}
+
+pub fn cell_variable(size: usize, (x, y): Point, n: u8) -> i32 {
+    ((y * size + x) * size + n as usize) as i32
+}
+
+fn at_most_one(clauses: &mut Vec<Vec<i32>>, literals: &[i32]) {
+    for (a, b) in literals.iter().tuple_combinations() {
+        clauses.push(vec![-a, -b]);
+    }
+}
+
+impl Cnf {
+    pub fn new(grid: &Grid) -> Cnf {
+        let size = grid.x;
+        let digits = 1..=size as u8;
+        let var = |pos: Point, n: u8| cell_variable(size, pos, n);
+        let mut clauses = Vec::new();
+
+        for (pos, cell) in grid.iter_by_cells() {
+            let candidates = match cell {
+                Requirement(n) | Solution(n) => [n].into_iter().collect(),
+                Indeterminate(set) => set,
+                Blocker(_) | Black => continue,
+            };
+            clauses.push(candidates.into_iter().map(|n| var(pos, n)).collect());
+            at_most_one(&mut clauses, &digits.clone().map(|n| var(pos, n)).collect::<Vec<_>>());
+            for n in digits.clone().filter(|&n| !candidates.contains(n)) {
+                clauses.push(vec![-var(pos, n)]);
+            }
+        }
+
+        for (vertical, line) in grid.iter_by_rows_and_cols() {
+            let index = if vertical { line[0].0 .0 } else { line[0].0 .1 };
+            let white = line
+                .iter()
+                .filter(|(_, cell)| cell.is_compartment_cell())
+                .map(|(pos, _)| *pos)
+                .collect::<Vec<_>>();
+            let blocked = line
+                .iter()
+                .filter_map(|(_, cell)| if let Blocker(n) = cell { Some(*n) } else { None })
+                .chain(grid.forbidden(vertical, (index, index)))
+                .collect::<Vec<_>>();
+            for n in digits.clone() {
+                let literals = white.iter().map(|&pos| var(pos, n)).collect::<Vec<_>>();
+                if blocked.contains(&n) {
+                    clauses.extend(literals.iter().map(|&lit| vec![-lit]));
+                } else {
+                    at_most_one(&mut clauses, &literals);
+                }
+                if grid.requirements(vertical, (index, index)).contains(n) {
+                    clauses.push(literals);
+                }
+            }
+        }
+
+        let mut variables = size * size * size;
+        let compartments: Vec<Compartment> = grid.iter_by_compartments();
+        for compartment in compartments {
+            let len = compartment.cells.len();
+            let starts = (1..=size + 1 - len)
+                .map(|start| {
+                    variables += 1;
+                    (start as u8, variables as i32)
+                })
+                .collect::<Vec<_>>();
+            clauses.push(starts.iter().map(|&(_, lit)| lit).collect());
+            at_most_one(&mut clauses, &starts.iter().map(|&(_, lit)| lit).collect::<Vec<_>>());
+            for &(start, lit) in &starts {
+                let range = start..start + len as u8;
+                for (pos, _) in &compartment.cells {
+                    for n in digits.clone().filter(|n| !range.contains(n)) {
+                        clauses.push(vec![-lit, -var(*pos, n)]);
+                    }
+                }
+            }
+        }
+
+        Cnf { variables, clauses }
+    }
+
+    pub fn to_dimacs(&self) -> String {
+        let mut out = format!("c str8ts puzzle\np cnf {} {}\n", self.variables, self.clauses.len());
+        for clause in &self.clauses {
+            out.push_str(&clause.iter().map(|lit| lit.to_string()).join(" "));
+            out.push_str(" 0\n");
+        }
+        out
+    }
+
+    /* Whether the true variables given satisfy every clause, for checking solver output */
+    pub fn is_satisfied_by(&self, true_variables: &[i32]) -> bool {
+        self.clauses.iter().all(|clause| {
+            clause
+                .iter()
+                .any(|&lit| (lit > 0) == true_variables.contains(&lit.abs()))
+        })
+    }
+}
+
+/* Fills the indeterminate cells with the numbers in `values`, row by row, 0 marking a cell without a number */
+pub(crate) fn apply_values(grid: &Grid, values: &[u8]) -> Result<Grid, String> {
+    if values.len() != grid.x * grid.y {
+        return Err(format!("Expected {} cells, got {}", grid.x * grid.y, values.len()));
+    }
+    let mut res = grid.clone();
+    for ((x, y), cell) in grid.iter_by_cells() {
+        let n = values[y * grid.x + x];
+        match cell {
+            Indeterminate(_) if n == 0 => return Err(format!("No number for cell ({}, {})", x + 1, y + 1)),
+            Indeterminate(_) | Solution(_) => res.set_cell((x, y), Solution(n)),
+            Requirement(m) if m != n => return Err(format!("Cell ({}, {}) should be {}", x + 1, y + 1, m)),
+            _ => {}
+        }
+    }
+    Ok(res)
+}
+
+/* Reads a model from SAT solver output, either the `v 1 -2 ...` lines of the competition format or the plain
+ * list of literals MiniSat writes */
+pub fn true_variables(output: &str) -> Result<Vec<i32>, String> {
+    let mut res = Vec::new();
+    for line in output.lines() {
+        let line = line.trim();
+        let line = line.strip_prefix("v ").unwrap_or(line);
+        if line.contains("UNSAT") {
+            return Err("The puzzle has no solutions".to_string());
+        }
+        if line.starts_with(['c', 's', 'S']) {
+            continue;
+        }
+        for token in line.split_whitespace() {
+            let lit = token
+                .parse::<i32>()
+                .map_err(|_| format!("Unexpected '{}' in the assignment", token))?;
+            if lit > 0 {
+                res.push(lit);
+            }
+        }
+    }
+    Ok(res)
+}
+
+pub fn decode_assignment(grid: &Grid, output: &str) -> Result<Grid, String> {
+    let true_variables = true_variables(output)?;
+    let size = grid.x;
+    let values = (0..size * size)
+        .map(|i| {
+            let pos = (i % size, i / size);
+            let digits = (1..=size as u8)
+                .filter(|&n| true_variables.contains(&cell_variable(size, pos, n)))
+                .collect::<Vec<_>>();
+            match digits[..] {
+                [] => Ok(0),
+                [n] => Ok(n),
+                _ => Err(format!("Cell ({}, {}) has several numbers", pos.0 + 1, pos.1 + 1)),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    apply_values(grid, &values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mistakes::solve_puzzle;
+    use crate::utils::*;
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    const PUZZLE: &str = "
+32.d.
+#1##3
+.432.
+a....
+.....
+";
+
+    fn assignment(grid: &Grid) -> Vec<i32> {
+        grid.iter_by_cells()
+            .into_iter()
+            .filter_map(|(pos, cell)| cell.to_req_or_sol().map(|n| cell_variable(grid.x, pos, n)))
+            .collect()
+    }
+
+    /* Plain DPLL, enough for small puzzles */
+    fn solve(clauses: &[Vec<i32>], assigned: &mut Vec<i32>) -> bool {
+        loop {
+            let mut unit = None;
+            for clause in clauses {
+                if clause.iter().any(|lit| assigned.contains(lit)) {
+                    continue;
+                }
+                let open = clause
+                    .iter()
+                    .filter(|lit| !assigned.contains(&-**lit))
+                    .collect::<Vec<_>>();
+                match open[..] {
+                    [] => return false,
+                    [lit] => {
+                        unit = Some(*lit);
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            match unit {
+                Some(lit) => assigned.push(lit),
+                None => break,
+            }
+        }
+        let Some(&lit) = clauses
+            .iter()
+            .flatten()
+            .find(|lit| !assigned.contains(lit) && !assigned.contains(&-**lit))
+        else {
+            return true;
+        };
+        for choice in [lit, -lit] {
+            let mut attempt = assigned.clone();
+            attempt.push(choice);
+            if solve(clauses, &mut attempt) {
+                *assigned = attempt;
+                return true;
+            }
+        }
+        false
+    }
+
+    fn model(cnf: &Cnf) -> Option<String> {
+        let mut assigned = Vec::new();
+        solve(&cnf.clauses, &mut assigned).then(|| assigned.iter().map(|lit| format!("v {}\n", lit)).collect())
+    }
+
+    #[test]
+    fn test_solution_is_a_model() {
+        let grid = g(PUZZLE);
+        let solution = solve_puzzle(&grid).unwrap();
+        let cnf = Cnf::new(&grid);
+        let mut model = assignment(&solution);
+        assert!(!cnf.is_satisfied_by(&model));
+
+        /* the compartment straight variables are not part of the grid */
+        let mut starts = Vec::new();
+        for compartment in grid.iter_by_compartments() {
+            let min = compartment
+                .cells
+                .iter()
+                .map(|(pos, _)| solution.get_cell(*pos).to_req_or_sol().unwrap())
+                .min();
+            starts.push(min.unwrap());
+        }
+        let mut variable = (grid.x * grid.x * grid.x) as i32;
+        for (compartment, start) in grid.iter_by_compartments().iter().zip(starts) {
+            model.push(variable + start as i32);
+            variable += (grid.x + 1 - compartment.cells.len()) as i32;
+        }
+        assert_eq!(variable as usize, cnf.variables);
+        assert!(cnf.is_satisfied_by(&model));
+
+        let text = model.iter().map(|lit| lit.to_string()).join(" ");
+        assert_eq!(decode_assignment(&grid, &format!("SAT\n{} 0\n", text)).unwrap().cells, solution.cells);
+        assert!(decode_assignment(&grid, "s UNSATISFIABLE").is_err());
+
+        let dimacs = cnf.to_dimacs();
+        assert!(dimacs.starts_with(&format!("c str8ts puzzle\np cnf {} {}\n", cnf.variables, cnf.clauses.len())));
+        assert_eq!(dimacs.lines().count(), cnf.clauses.len() + 2);
+    }
+
+    #[test]
+    fn test_unique_model() {
+        let grid = g(PUZZLE);
+        let solution = solve_puzzle(&grid).unwrap();
+        let mut cnf = Cnf::new(&grid);
+        let output = model(&cnf).unwrap();
+        assert_eq!(decode_assignment(&grid, &output).unwrap().cells, solution.cells);
+
+        cnf.clauses.push(assignment(&solution).iter().map(|lit| -lit).collect());
+        assert_eq!(model(&cnf), None);
+    }
+
+    /* Needs minisat on the PATH, run with `cargo test -p solver -- --ignored test_minisat` */
+    #[test]
+    #[ignore]
+    fn test_minisat() {
+        let grid = g(PUZZLE);
+        let dir = std::env::temp_dir().join(format!("str8ts-cnf-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (input, output) = (dir.join("puzzle.cnf"), dir.join("model.txt"));
+        std::fs::File::create(&input)
+            .unwrap()
+            .write_all(Cnf::new(&grid).to_dimacs().as_bytes())
+            .unwrap();
+        let status = Command::new("minisat")
+            .arg(&input)
+            .arg(&output)
+            .stdout(Stdio::null())
+            .status()
+            .expect("failed to run minisat");
+        /* minisat exits with 10 for satisfiable formulas */
+        assert_eq!(status.code(), Some(10));
+        let model = std::fs::read_to_string(&output).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(decode_assignment(&grid, &model).unwrap().cells, solve_puzzle(&grid).unwrap().cells);
+    }
+}
@@ -0,0 +1,106 @@
+//! Cell coordinates as shown to the user
+
+use crate::grid::Point;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Hash)]
+pub enum Notation {
+    /* (x, y), counting from 1 */
+    #[default]
+    XY,
+    /* R1C1, row first */
+    RowCol,
+    /* A1, column letter followed by row number, with A1 in the top left corner */
+    Chess,
+}
+
+pub const NOTATIONS: &[Notation] = &[Notation::XY, Notation::RowCol, Notation::Chess];
+
+impl Notation {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Notation::XY => "xy",
+            Notation::RowCol => "r1c1",
+            Notation::Chess => "a1",
+        }
+    }
+}
+
+impl FromStr for Notation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "xy" | "(x, y)" => Ok(Notation::XY),
+            "r1c1" | "rc" => Ok(Notation::RowCol),
+            "a1" | "chess" => Ok(Notation::Chess),
+            _ => Err(format!("Unknown coordinate notation '{}'", s)),
+        }
+    }
+}
+
+impl Display for Notation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+/* A zero-based grid position, rendered one-based in the chosen notation */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Coordinate(pub Point);
+
+impl Coordinate {
+    pub fn format(&self, notation: Notation) -> String {
+        let (x, y) = self.0;
+        match notation {
+            Notation::XY => format!("({}, {})", x + 1, y + 1),
+            Notation::RowCol => format!("R{}C{}", y + 1, x + 1),
+            Notation::Chess => format!("{}{}", column_letters(x), y + 1),
+        }
+    }
+}
+
+/* A..Z, then AA, AB, ... like spreadsheet columns */
+fn column_letters(mut x: usize) -> String {
+    let mut letters = Vec::new();
+    loop {
+        letters.push((b'A' + (x % 26) as u8) as char);
+        if x < 26 {
+            break;
+        }
+        x = x / 26 - 1;
+    }
+    letters.iter().rev().collect()
+}
+
+impl From<Point> for Coordinate {
+    fn from(pos: Point) -> Self {
+        Coordinate(pos)
+    }
+}
+
+impl Display for Coordinate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.format(Notation::default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notations() {
+        let pos = Coordinate((2, 0));
+        assert_eq!(pos.format(Notation::XY), "(3, 1)");
+        assert_eq!(pos.format(Notation::RowCol), "R1C3");
+        assert_eq!(pos.format(Notation::Chess), "C1");
+        assert_eq!(pos.to_string(), "(3, 1)");
+
+        assert_eq!(Coordinate((25, 8)).format(Notation::Chess), "Z9");
+        assert_eq!(Coordinate((26, 8)).format(Notation::Chess), "AA9");
+        assert_eq!("R1C1".parse(), Ok(Notation::RowCol));
+        assert_eq!("chess".parse(), Ok(Notation::Chess));
+    }
+}
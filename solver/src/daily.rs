@@ -0,0 +1,224 @@
+//! Puzzle of the day, derived from the date alone so that every instance shows the same puzzle
+//!
+//! The seed is a hash of the algorithm version, the date, the difficulty and a salt. The puzzles are generated
+//! on `rand_chacha::ChaCha8Rng` with fixed parameters and their own strategy list, removing numbers in a fixed
+//! order so that the number of threads doesn't matter. New strategies therefore don't change the puzzles, but
+//! any change to how the strategies of `DAILY_STRATEGIES` behave or are rated, or to the generator itself, must
+//! bump `DAILY_VERSION`. That changes every future puzzle but keeps them consistent between instances running
+//! the same version.
+//!
+//! The salt is read from the `STR8TS_DAILY_SALT` environment variable at compile time, so that the puzzles
+//! cannot be generated in advance without it.
+
+use crate::difficulty::get_puzzle_difficulty;
+use crate::generator::{generate_solved_grid_with, remove_numbers_in_order};
+use crate::grid::Grid;
+use crate::strategy::{Strategy, StrategyList};
+use rand::{RngCore, SeedableRng};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/* 1: first version
+ * 2: own strategy list, and removing numbers no longer depends on the thread count
+ * 3: unique requirement guessing is back, with a closed set check
+ * 4: the row and column brute force became a line solver, which also considers 9 */
+pub const DAILY_VERSION: u32 = 4;
+
+pub const DAILY_SALT: &str = match option_env!("STR8TS_DAILY_SALT") {
+    Some(salt) => salt,
+    None => "str8ts-solver",
+};
+
+const SIZE: usize = 9;
+const BLOCKER_COUNT: usize = 15;
+const BLOCKER_NUM_COUNT: usize = 5;
+
+/* The strategies as of version 2, which the puzzles are generated and rated with */
+const DAILY_STRATEGIES: &[Strategy] = &[
+    Strategy::UpdateImpossibles,
+    Strategy::Singles,
+    Strategy::Stranded,
+    Strategy::DefiniteMinMax,
+    Strategy::RequiredRange,
+    Strategy::Sets,
+    Strategy::RequiredAndForbidden,
+    Strategy::RowColBrute,
+    Strategy::Setti,
+    Strategy::YWing,
+    Strategy::Fish,
+    Strategy::Medusa,
+    Strategy::UniqueRequirement,
+    Strategy::UniqueRequirementGuess,
+    Strategy::Guess,
+    Strategy::EnumerateSolutions,
+];
+
+fn daily_strategies(max_difficulty: usize) -> StrategyList {
+    StrategyList::new(
+        &DAILY_STRATEGIES
+            .iter()
+            .copied()
+            .filter(|strat| strat.difficulty() <= max_difficulty)
+            .collect::<Vec<_>>(),
+    )
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl Date {
+    pub fn new(year: i32, month: u32, day: u32) -> Result<Date, String> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return Err(format!("Invalid date {:04}-{:02}-{:02}", year, month, day));
+        }
+        Ok(Date { year, month, day })
+    }
+
+    /* Civil date from days since 1970-01-01, see http://howardhinnant.github.io/date_algorithms.html */
+    pub fn from_days(days: i64) -> Date {
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+        Date { year, month, day }
+    }
+
+    /* The current date in UTC */
+    pub fn today() -> Date {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Date::from_days((secs / 86400) as i64)
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s.trim().split('-').collect::<Vec<_>>();
+        let [year, month, day] = parts[..] else {
+            return Err(format!("Expected a date like 2024-01-31, got '{}'", s));
+        };
+        let number = |part: &str| part.parse().map_err(|_| format!("Invalid date '{}'", s));
+        Date::new(number(year)? as i32, number(month)?, number(day)?)
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/* 64-bit FNV-1a, which unlike the std hashers is guaranteed to stay the same */
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0xcbf29ce484222325, |hash, &b| (hash ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
+pub fn daily_seed(date: Date, difficulty: usize, salt: &str) -> u64 {
+    fnv1a(format!("v{}:{}:{}:{}", DAILY_VERSION, date, difficulty, salt).as_bytes())
+}
+
+/* Like generator_loop, with the daily strategies and removal order */
+fn generate(size: usize, blocker_count: usize, blocker_num_count: usize, difficulty: usize, first_seed: u64) -> Grid {
+    /* no strategy is rated three stars */
+    let difficulty = if difficulty == 3 { 4 } else { difficulty };
+    let fill_strats = daily_strategies(usize::MAX).except(&[
+        Strategy::UniqueRequirementGuess,
+        Strategy::Guess,
+        Strategy::EnumerateSolutions,
+    ]);
+    let strats = daily_strategies(difficulty);
+    let mut seed = first_seed;
+    loop {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(seed);
+        if let Some(grid) =
+            generate_solved_grid_with(size, blocker_count, blocker_num_count, true, &fill_strats, &mut rng)
+        {
+            let grid = remove_numbers_in_order(grid, &strats, difficulty, true, &mut rng);
+            let rating = get_puzzle_difficulty(&grid, &daily_strategies(usize::MAX));
+            if rating.is_some_and(|rating| rating.star_count == difficulty) {
+                return grid;
+            }
+        }
+        seed = rng.next_u64();
+    }
+}
+
+pub fn daily_with_salt(date: Date, difficulty: usize, salt: &str) -> Grid {
+    /* every puzzle needs at least singles, so there are no one star puzzles to find */
+    let difficulty = difficulty.clamp(2, 7);
+    generate(SIZE, BLOCKER_COUNT, BLOCKER_NUM_COUNT, difficulty, daily_seed(date, difficulty, salt))
+}
+
+pub fn daily(date: Date, difficulty: usize) -> Grid {
+    daily_with_salt(date, difficulty, DAILY_SALT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle_coding::encode;
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(Date::from_days(0), Date::new(1970, 1, 1).unwrap());
+        assert_eq!(Date::from_days(19782), Date::new(2024, 2, 29).unwrap());
+        assert_eq!(Date::from_days(-1), Date::new(1969, 12, 31).unwrap());
+        assert_eq!("2024-02-29".parse::<Date>().unwrap().to_string(), "2024-02-29");
+        assert!("2023-02-29".parse::<Date>().is_err());
+        assert!("2023-13-01".parse::<Date>().is_err());
+        assert!("yesterday".parse::<Date>().is_err());
+    }
+
+    #[test]
+    fn test_daily_is_pinned() {
+        let date = Date::new(2024, 1, 1).unwrap();
+        assert_eq!(daily_seed(date, 2, "salt"), 16494392850008451603);
+        assert_ne!(daily_seed(date, 2, "salt"), daily_seed(date, 4, "salt"));
+        assert_ne!(daily_seed(date, 2, "salt"), daily_seed(date, 2, "pepper"));
+        assert_ne!(daily_seed(date, 2, "salt"), daily_seed(Date::new(2024, 1, 2).unwrap(), 2, "salt"));
+
+        /* A full daily puzzle is too slow to generate here, but a small grid from the same seed catches changes
+         * to the generator. If this fails, bump DAILY_VERSION and update the expected grid. */
+        let grid = generate(5, 4, 1, 2, daily_seed(date, 2, "salt"));
+        assert_eq!(encode(&grid), "T5B1o1o011o1o0a1o0p1o1o1o0a1o0a1o1o1o031o0a1o1o041o1o");
+    }
+
+    #[test]
+    fn test_daily_ignores_thread_count() {
+        let seed = daily_seed(Date::new(2024, 1, 1).unwrap(), 2, "pepper");
+        let generate = |threads| {
+            let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+            pool.install(|| generate(5, 4, 1, 2, seed))
+        };
+        assert_eq!(generate(1), generate(4));
+    }
+}
@@ -2,8 +2,10 @@ use crate::grid::Grid;
 use crate::solve_result::{into_ty, SolveType};
 use crate::solver::run_strat;
 use crate::strategy::{Strategy, StrategyList};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Difficulty {
     pub star_count: usize,
     pub move_count: usize,
@@ -12,17 +14,57 @@ pub struct Difficulty {
     pub cross_compartment_ranges: bool,
     pub maintain_reqs_and_blocks: bool,
     pub sets: bool,
+    pub hidden_sets: bool,
     pub setti: bool,
     pub y_wing: bool,
+    pub xyz_wing: bool,
     pub x_wing: bool,
     pub swordfish: bool,
     pub n_fish: usize,
+    pub x_chain: bool,
+    pub finned_fish: bool,
+    pub xy_chain: bool,
     pub medusa: bool,
+    pub aic: bool,
     pub unique_requirement: bool,
+    pub pattern_overlay: bool,
+    pub forcing_chain: bool,
     pub short_guess_count: usize,
     pub long_guess_count: usize,
 }
 
+impl Difficulty {
+    /* Names of the techniques the solution needed, as used by the puzzle library filters */
+    pub fn techniques(&self) -> Vec<&'static str> {
+        [
+            ("min_max", self.min_max_reductions),
+            ("required_range", self.cross_compartment_ranges),
+            ("required_and_forbidden", self.maintain_reqs_and_blocks),
+            ("sets", self.sets),
+            ("hidden_sets", self.hidden_sets),
+            ("setti", self.setti),
+            ("y_wing", self.y_wing),
+            ("xyz_wing", self.xyz_wing),
+            ("x_wing", self.x_wing),
+            ("swordfish", self.swordfish),
+            ("fish", self.n_fish > 3),
+            ("x_chain", self.x_chain),
+            ("finned_fish", self.finned_fish),
+            ("xy_chain", self.xy_chain),
+            ("medusa", self.medusa),
+            ("aic", self.aic),
+            ("unique_requirement", self.unique_requirement),
+            ("pattern_overlay", self.pattern_overlay),
+            ("forcing_chain", self.forcing_chain),
+            ("guess", self.short_guess_count + self.long_guess_count > 0),
+        ]
+        .into_iter()
+        .filter(|(_, used)| *used)
+        .map(|(name, _)| name)
+        .collect()
+    }
+}
+
 pub fn puzzle_difficulty(history: &[&SolveType]) -> Difficulty {
     let move_count = history.len();
 
@@ -39,9 +81,11 @@ pub fn puzzle_difficulty(history: &[&SolveType]) -> Difficulty {
         min_max_reductions: history.iter().any(|e| matches!(e, SolveType::DefiniteMinMax)),
         cross_compartment_ranges: history.iter().any(|e| matches!(e, SolveType::RequiredRange)),
         sets: history.iter().any(|e| matches!(e, SolveType::Sets(_))),
+        hidden_sets: history.iter().any(|e| matches!(e, SolveType::HiddenSets(_, _))),
         maintain_reqs_and_blocks: history.iter().any(|e| matches!(e, SolveType::RequiredAndForbidden)),
         setti: history.iter().any(|e| matches!(e, SolveType::Setti(_))),
         y_wing: history.iter().any(|e| matches!(e, SolveType::YWing(_, _))),
+        xyz_wing: history.iter().any(|e| matches!(e, SolveType::XyzWing(..))),
         x_wing: history.iter().any(|e| matches!(e, SolveType::Fish(2))),
         swordfish: history.iter().any(|e| matches!(e, SolveType::Fish(3))),
         n_fish: history
@@ -49,8 +93,14 @@ pub fn puzzle_difficulty(history: &[&SolveType]) -> Difficulty {
             .map(|e| if let SolveType::Fish(n) = e { *n } else { 0 })
             .max()
             .unwrap_or(0),
+        x_chain: history.iter().any(|e| matches!(e, SolveType::XChain(..))),
+        finned_fish: history.iter().any(|e| matches!(e, SolveType::FinnedFish(..))),
+        xy_chain: history.iter().any(|e| matches!(e, SolveType::XyChain(..))),
         medusa: history.iter().any(|e| matches!(e, SolveType::Medusa)),
+        aic: history.iter().any(|e| matches!(e, SolveType::Aic(..))),
         unique_requirement: history.iter().any(|e| matches!(e, SolveType::UniqueRequirement(..))),
+        pattern_overlay: history.iter().any(|e| matches!(e, SolveType::PatternOverlay(..))),
+        forcing_chain: history.iter().any(|e| matches!(e, SolveType::ForcingChain(..))),
         short_guess_count: history
             .iter()
             .filter(|e| matches!(e, SolveType::GuessStep(_, _, steps, _) if steps.len() < 8))
@@ -0,0 +1,425 @@
+use crate::bitset::BitSet;
+use crate::grid::Cell::*;
+use crate::grid::{Grid, Point};
+use crate::mistakes::{find_mistakes_with_solution, original_puzzle, solve_puzzle, Mistakes};
+use crate::solve_result::{SolveResults, SolveType, ValidationError, ValidationResult};
+use crate::solver::solve_round;
+use std::time::Duration;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Move {
+    SetNumber(Point, u8),
+    ClearCell(Point),
+    ToggleMark(Point, u8),
+    SetMarks(Point, BitSet),
+    AutoPruneMarks,
+    Hint(Point, u8),
+}
+
+/* Everything the player has changed on top of the original puzzle */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlayerState {
+    pub entries: Vec<Vec<Option<u8>>>,
+    pub marks: Vec<Vec<BitSet>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hint {
+    pub pos: Point,
+    pub num: u8,
+    pub steps: Vec<SolveResults>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Game {
+    pub puzzle: Grid,
+    pub state: PlayerState,
+    pub move_count: usize,
+    pub elapsed: Duration,
+    undo_stack: Vec<(Move, PlayerState)>,
+    redo_stack: Vec<(Move, PlayerState)>,
+    solution: Option<Grid>,
+}
+
+impl Game {
+    /* Solution cells in the grid are treated as existing player entries */
+    pub fn new(grid: &Grid) -> Result<Game, String> {
+        let entries = grid
+            .cells
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| if let Solution(n) = cell { Some(*n) } else { None })
+                    .collect()
+            })
+            .collect();
+        let marks = grid.cells.iter().map(|row| vec![BitSet::new(); row.len()]).collect();
+        Ok(Game {
+            puzzle: original_puzzle(grid)?,
+            state: PlayerState { entries, marks },
+            move_count: 0,
+            elapsed: Duration::ZERO,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            solution: None,
+        })
+    }
+
+    pub fn is_editable(&self, pos: Point) -> bool {
+        matches!(self.puzzle.get_cell(pos), Indeterminate(_))
+    }
+
+    pub fn entry(&self, pos: Point) -> Option<u8> {
+        self.state.entries[pos.1][pos.0]
+    }
+
+    pub fn marks(&self, pos: Point) -> BitSet {
+        self.state.marks[pos.1][pos.0]
+    }
+
+    /* The solver's view of the game: givens and player entries, with unrestricted candidates */
+    pub fn grid(&self) -> Grid {
+        let mut grid = self.puzzle.clone();
+        for (pos, _) in self.puzzle.iter_by_indeterminates() {
+            if let Some(n) = self.entry(pos) {
+                grid.set_cell(pos, Solution(n));
+            }
+        }
+        grid
+    }
+
+    /* Like grid(), but with the player's pencil marks as candidates */
+    pub fn marked_grid(&self) -> Grid {
+        let mut grid = self.grid();
+        for (pos, _) in grid.iter_by_indeterminates() {
+            let marks = self.marks(pos);
+            if !marks.is_empty() {
+                grid.set_cell(pos, Indeterminate(marks));
+            }
+        }
+        grid
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.grid().is_solved() && self.mistakes().map(|m| m.is_empty()).unwrap_or(false)
+    }
+
+    pub fn solution(&mut self) -> Result<&Grid, ValidationResult> {
+        if self.solution.is_none() {
+            self.solution = Some(solve_puzzle(&self.puzzle)?);
+        }
+        Ok(self.solution.as_ref().unwrap())
+    }
+
+    pub fn mistakes(&self) -> Result<Mistakes, ValidationResult> {
+        let solution = match &self.solution {
+            Some(solution) => solution.clone(),
+            None => solve_puzzle(&self.puzzle)?,
+        };
+        Ok(find_mistakes_with_solution(&self.marked_grid(), &solution))
+    }
+
+    pub fn tick(&mut self, elapsed: Duration) {
+        self.elapsed += elapsed;
+    }
+
+    pub fn apply(&mut self, mv: Move) -> bool {
+        let before = self.state.clone();
+        let changed = match mv {
+            Move::SetNumber(pos, num) => {
+                if !self.is_editable(pos) || num == 0 || num as usize > self.puzzle.x || self.entry(pos) == Some(num) {
+                    false
+                } else {
+                    self.state.entries[pos.1][pos.0] = Some(num);
+                    true
+                }
+            }
+            Move::ClearCell(pos) => {
+                let had_entry = self.state.entries[pos.1][pos.0].take().is_some();
+                let had_marks = !self.marks(pos).is_empty();
+                self.state.marks[pos.1][pos.0] = BitSet::new();
+                had_entry || had_marks
+            }
+            Move::ToggleMark(pos, num) => {
+                if !self.is_editable(pos) || num == 0 || num as usize > self.puzzle.x {
+                    false
+                } else {
+                    let marks = &mut self.state.marks[pos.1][pos.0];
+                    if !marks.remove(num) {
+                        marks.insert(num);
+                    }
+                    true
+                }
+            }
+            Move::SetMarks(pos, set) => {
+                if !self.is_editable(pos) || self.marks(pos) == set {
+                    false
+                } else {
+                    self.state.marks[pos.1][pos.0] = set;
+                    true
+                }
+            }
+            Move::AutoPruneMarks => self.prune_marks(),
+            Move::Hint(pos, num) => {
+                if !self.is_editable(pos) || self.entry(pos) == Some(num) {
+                    false
+                } else {
+                    self.state.entries[pos.1][pos.0] = Some(num);
+                    true
+                }
+            }
+        };
+
+        if changed {
+            self.move_count += 1;
+            self.undo_stack.push((mv, before));
+            self.redo_stack.clear();
+        }
+        changed
+    }
+
+    pub fn set_number(&mut self, pos: Point, num: u8) -> bool {
+        self.apply(Move::SetNumber(pos, num))
+    }
+
+    pub fn clear_cell(&mut self, pos: Point) -> bool {
+        self.apply(Move::ClearCell(pos))
+    }
+
+    pub fn toggle_mark(&mut self, pos: Point, num: u8) -> bool {
+        self.apply(Move::ToggleMark(pos, num))
+    }
+
+    pub fn set_marks(&mut self, pos: Point, set: BitSet) -> bool {
+        self.apply(Move::SetMarks(pos, set))
+    }
+
+    /* Remove pencil marks which are already present in the same row or column */
+    pub fn auto_prune_marks(&mut self) -> bool {
+        self.apply(Move::AutoPruneMarks)
+    }
+
+    fn prune_marks(&mut self) -> bool {
+        let grid = self.grid();
+        let mut changes = false;
+        for (pos, _) in grid.iter_by_indeterminates() {
+            let seen: BitSet = grid
+                .get_row(pos.1)
+                .into_iter()
+                .chain(grid.get_col(pos.0))
+                .filter_map(|(_, cell)| cell.to_determinate())
+                .collect();
+            let marks = self.marks(pos);
+            let pruned = marks.difference(seen);
+            if pruned != marks {
+                self.state.marks[pos.1][pos.0] = pruned;
+                changes = true;
+            }
+        }
+        changes
+    }
+
+    /* Run the solver on the current entries until it solves a new cell */
+    pub fn hint(&self) -> Result<Option<Hint>, ValidationResult> {
+        let mut grid = self.grid();
+        let mut steps = Vec::new();
+        loop {
+            let step = solve_round(&mut grid, true)?;
+            if step.ty == SolveType::PuzzleSolved && steps.is_empty() {
+                return Ok(None);
+            }
+            let solved = self
+                .puzzle
+                .iter_by_indeterminates()
+                .into_iter()
+                .map(|(pos, _)| pos)
+                .filter(|&pos| self.entry(pos).is_none())
+                .find_map(|pos| grid.get_cell(pos).to_req_or_sol().map(|num| (pos, num)));
+            let done = step.ty == SolveType::PuzzleSolved;
+            steps.push(step);
+            if let Some((pos, num)) = solved {
+                return Ok(Some(Hint { pos, num, steps }));
+            }
+            if done {
+                return Err(ValidationError::OutOfStrats.into());
+            }
+        }
+    }
+
+    pub fn apply_hint(&mut self) -> Result<Option<Hint>, ValidationResult> {
+        let hint = self.hint()?;
+        if let Some(Hint { pos, num, .. }) = hint {
+            self.apply(Move::Hint(pos, num));
+        }
+        Ok(hint)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    pub fn undo(&mut self) -> Option<Move> {
+        let (mv, before) = self.undo_stack.pop()?;
+        let after = std::mem::replace(&mut self.state, before);
+        self.redo_stack.push((mv.clone(), after));
+        Some(mv)
+    }
+
+    pub fn redo(&mut self) -> Option<Move> {
+        let (mv, after) = self.redo_stack.pop()?;
+        let before = std::mem::replace(&mut self.state, after);
+        self.undo_stack.push((mv.clone(), before));
+        Some(mv)
+    }
+
+    pub fn history(&self) -> Vec<&Move> {
+        self.undo_stack.iter().map(|(mv, _)| mv).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::*;
+
+    const PUZZLE: &str = "
+12#.7...6
+.#.4..3..
+.#.#i....
+7..3.....
+9#3#.#8#7
+.....1..4
+....h#.#.
+..9..8.#.
+5...4.#32
+";
+
+    #[test]
+    fn test_entries_and_undo() {
+        let mut game = Game::new(&g(PUZZLE)).unwrap();
+
+        assert!(!game.set_number((0, 0), 3));
+        assert!(game.set_number((3, 0), 5));
+        assert!(!game.set_number((3, 0), 5));
+        assert!(game.toggle_mark((5, 0), 3));
+        assert!(game.toggle_mark((5, 0), 9));
+        assert_eq!(game.marks((5, 0)), set([3, 9]));
+        assert_eq!(game.move_count, 3);
+
+        assert_eq!(game.undo(), Some(Move::ToggleMark((5, 0), 9)));
+        assert_eq!(game.marks((5, 0)), set([3]));
+        assert_eq!(game.undo(), Some(Move::ToggleMark((5, 0), 3)));
+        assert_eq!(game.undo(), Some(Move::SetNumber((3, 0), 5)));
+        assert_eq!(game.entry((3, 0)), None);
+        assert_eq!(game.undo(), None);
+
+        assert_eq!(game.redo(), Some(Move::SetNumber((3, 0), 5)));
+        assert_eq!(game.entry((3, 0)), Some(5));
+        assert!(game.can_redo());
+
+        assert!(game.clear_cell((3, 0)));
+        assert!(!game.can_redo());
+        assert_eq!(game.history().len(), 2);
+    }
+
+    #[test]
+    fn test_marks_are_separate_from_candidates() {
+        let mut game = Game::new(&g(PUZZLE)).unwrap();
+        game.set_marks((3, 0), set([5, 8]));
+
+        assert_eq!(game.grid().get_cell((3, 0)), &det([1, 2, 3, 4, 5, 6, 7, 8, 9]));
+        assert_eq!(game.marked_grid().get_cell((3, 0)), &det([5, 8]));
+    }
+
+    #[test]
+    fn test_auto_prune() {
+        let mut game = Game::new(&g(PUZZLE)).unwrap();
+        game.set_marks((3, 0), set([1, 4, 5, 6]));
+        game.set_marks((5, 0), set([3, 9]));
+        game.set_number((7, 0), 9);
+
+        assert!(game.auto_prune_marks());
+        assert_eq!(game.marks((3, 0)), set([5]));
+        assert_eq!(game.marks((5, 0)), set([3]));
+        assert!(!game.auto_prune_marks());
+
+        game.undo();
+        assert_eq!(game.marks((3, 0)), set([1, 4, 5, 6]));
+    }
+
+    #[test]
+    fn test_hint_and_mistakes() {
+        let mut game = Game::new(&g(PUZZLE)).unwrap();
+        let hint = game.apply_hint().unwrap().unwrap();
+        assert!(!hint.steps.is_empty());
+        assert_eq!(game.entry(hint.pos), Some(hint.num));
+        assert_eq!(game.solution().unwrap().get_cell(hint.pos), &Solution(hint.num));
+        assert_eq!(game.history(), vec![&Move::Hint(hint.pos, hint.num)]);
+
+        game.set_number((6, 0), 3);
+        assert_eq!(game.mistakes().unwrap().wrong_entries, vec![((6, 0), 3, 4)]);
+        assert!(!game.is_solved());
+    }
+
+    #[test]
+    fn test_small_game_to_completion() {
+        let mut game = Game::new(&g("
+1.#4
+..#.
+#...
+4..#
+"))
+        .unwrap();
+        assert!(!game.set_number((1, 0), 5));
+        assert!(!game.toggle_mark((1, 0), 0));
+        assert!(!game.set_number((2, 0), 3));
+
+        while game.apply_hint().unwrap().is_some() {}
+        assert!(game.grid().is_solved());
+        assert!(game.is_solved());
+        assert_eq!(game.hint(), Ok(None));
+    }
+
+    #[test]
+    fn test_entries_from_grid() {
+        let mut grid = g(PUZZLE);
+        grid.set_cell((3, 0), Solution(5));
+        let game = Game::new(&grid).unwrap();
+
+        assert!(game.is_editable((3, 0)));
+        assert_eq!(game.entry((3, 0)), Some(5));
+        assert_eq!(game.grid(), grid);
+        assert!(!game.can_undo());
+    }
+
+    #[test]
+    fn test_malformed_grid() {
+        let mut grid = g(PUZZLE);
+        grid.cells.pop();
+        assert!(Game::new(&grid).is_err());
+    }
+
+    #[test]
+    fn test_ambiguous_puzzle() {
+        let mut game = Game::new(&g("
+#4..
+..#.
+.#..
+..4#
+"))
+        .unwrap();
+        assert!(matches!(
+            game.mistakes(),
+            Err(ValidationResult {
+                ty: ValidationError::Ambiguous { .. },
+                ..
+            })
+        ));
+        assert!(game.solution().is_err());
+        assert!(!game.is_solved());
+    }
+}
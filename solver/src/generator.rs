@@ -37,6 +37,10 @@ impl<T: Ord, R> PartialOrd for Task<T, R> {
 }
 
 pub fn fill_numbers<Rand: Rng + Send + Clone>(grid: Grid, rng: &mut Rand) -> Option<Grid> {
+    fill_numbers_with(grid, &StrategyList::no_guesses(), rng)
+}
+
+fn fill_numbers_with<Rand: Rng + Send + Clone>(grid: Grid, strats: &StrategyList, rng: &mut Rand) -> Option<Grid> {
     let mut queue = BinaryHeap::new();
     let mut max_depth = 0;
 
@@ -89,7 +93,7 @@ pub fn fill_numbers<Rand: Rng + Send + Clone>(grid: Grid, rng: &mut Rand) -> Opt
                     let mut new_grid = grid.clone();
                     new_grid.set_cell(pos, Cell::Requirement(num));
                     loop {
-                        match into_ty(run_strat(&mut new_grid, &StrategyList::no_guesses())) {
+                        match into_ty(run_strat(&mut new_grid, strats)) {
                             Err(ValidationError::OutOfStrats) | Ok(SolveType::PuzzleSolved) => break,
                             Ok(_) => {}
                             Err(_) => return None,
@@ -112,6 +116,18 @@ pub fn generate_solved_grid<Rand: Rng + Send + Clone>(
     blocker_num_count: usize,
     symmetric: bool,
     rng: &mut Rand,
+) -> Option<Grid> {
+    generate_solved_grid_with(size, blocker_count, blocker_num_count, symmetric, &StrategyList::no_guesses(), rng)
+}
+
+/* Like generate_solved_grid, filling in the numbers with the given strategies */
+pub(crate) fn generate_solved_grid_with<Rand: Rng + Send + Clone>(
+    size: usize,
+    blocker_count: usize,
+    blocker_num_count: usize,
+    symmetric: bool,
+    strats: &StrategyList,
+    rng: &mut Rand,
 ) -> Option<Grid> {
     let mut grid = Grid::parse(vec![format!("{}", "0".repeat(2 * size * size))]).unwrap();
 
@@ -169,7 +185,7 @@ pub fn generate_solved_grid<Rand: Rng + Send + Clone>(
 
     debug!("Attempting to generate\n{}", grid);
 
-    fill_numbers(grid, rng)
+    fill_numbers_with(grid, strats, rng)
 }
 
 fn get_grid_hash(grid: &Grid) -> u128 {
@@ -293,6 +309,90 @@ pub fn remove_numbers<Rand: Rng + Send + Clone>(
     Some(x)
 }
 
+/* Tasks taken from the queue per round by remove_numbers_in_order */
+const POOL_SIZE: usize = 16;
+
+/* Like remove_numbers, but the removals are tried in an order fixed by the rng, so the result doesn't depend on
+ * the thread count. Only the ratings of the new grids run in parallel, and they are collected in order.
+ * `strats` are the strategies the puzzle may need. */
+pub(crate) fn remove_numbers_in_order<Rand: Rng + Send + Clone>(
+    grid: Grid,
+    strats: &StrategyList,
+    target_difficulty: usize,
+    symmetric: bool,
+    rng: &mut Rand,
+) -> Grid {
+    let mut queue: BinaryHeap<Task<Point, Rand>> = BinaryHeap::new();
+    queue.push(Task((0, 0), rng.clone(), grid.clone()));
+    let size = grid.y;
+
+    let diff = get_puzzle_difficulty(&grid, strats).unwrap();
+    let mut best_difficulty = (diff.star_count, diff.move_count);
+    let mut best_grid = grid;
+    let mut iterations = 0;
+
+    let mut seen = FxHashSet::<u128>::default();
+
+    while !queue.is_empty() {
+        iterations += 1;
+        debug!("queue len={} iter={} seen elements={}", queue.len(), iterations, seen.len());
+
+        let mut candidates = Vec::new();
+        for _ in 0..POOL_SIZE {
+            let Some(Task((star_count, move_count), mut rng, grid)) = queue.pop() else {
+                break;
+            };
+            if star_count > target_difficulty
+                || star_count < best_difficulty.0
+                || (star_count == best_difficulty.0 && move_count + 5 < best_difficulty.1)
+            {
+                continue;
+            } else if star_count > best_difficulty.0
+                || (star_count == best_difficulty.0 && move_count > best_difficulty.1)
+            {
+                debug!("New best, difficulty = {}:\n{}", star_count, grid);
+
+                best_difficulty = (star_count, move_count);
+                best_grid = grid.clone();
+            }
+
+            let mut positions = grid
+                .iter_by_cell_pos_matching(|cell| matches!(cell, Cell::Solution(_)))
+                .into_iter()
+                .map(|cand| (cand, rng.clone()))
+                .collect::<Vec<_>>();
+            positions.shuffle(&mut rng);
+
+            for ((x, y), mut rng) in positions {
+                let mut grid = grid.clone();
+                grid.set_cell((x, y), Cell::Indeterminate((1..=size as u8).collect()));
+                if symmetric {
+                    grid.set_cell((size - x - 1, size - y - 1), Cell::Indeterminate((1..=size as u8).collect()));
+                } else {
+                    let nx = rng.random_range(0..size);
+                    let ny = rng.random_range(0..size);
+                    if let Cell::Solution(_) = grid.get_cell((nx, ny)) {
+                        grid.set_cell((size - x - 1, size - y - 1), Cell::Indeterminate((1..=size as u8).collect()));
+                    }
+                }
+                if seen.insert(get_grid_hash(&grid)) {
+                    candidates.push((rng, grid));
+                }
+            }
+        }
+
+        let next_candidates = candidates
+            .into_par_iter()
+            .filter_map(|(rng, grid)| {
+                get_puzzle_difficulty(&grid, strats)
+                    .map(|difficulty| Task((difficulty.star_count, difficulty.move_count), rng, grid))
+            })
+            .collect::<Vec<_>>();
+        queue.extend(next_candidates);
+    }
+    best_grid
+}
+
 fn generate_puzzle<Rand: Rng + Send + Clone>(
     size: usize,
     blocker_count: usize,
@@ -329,12 +429,11 @@ pub fn generator_loop(
     }
     loop {
         let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(seed);
-        match generate_puzzle(size, blocker_count, blocker_num_count, target_difficulty, symmetric, &mut rng) {
-            None => {}
-            Some((grid, difficulty)) => {
-                if difficulty == target_difficulty {
-                    return grid;
-                }
+        if let Some((grid, difficulty)) =
+            generate_puzzle(size, blocker_count, blocker_num_count, target_difficulty, symmetric, &mut rng)
+        {
+            if difficulty == target_difficulty {
+                return grid;
             }
         }
         seed = rng.next_u64();
@@ -348,6 +447,9 @@ pub fn generator(
     target_difficulty: usize,
     symmetric: bool,
 ) -> Grid {
-    let mut rng = rand_chacha::ChaCha8Rng::from_seed(rng().random());
-    generator_loop(size, blocker_count, blocker_num_count, target_difficulty, symmetric, rng.next_u64())
+    generator_loop(size, blocker_count, blocker_num_count, target_difficulty, symmetric, random_seed())
+}
+
+pub fn random_seed() -> u64 {
+    rand_chacha::ChaCha8Rng::from_seed(rng().random()).next_u64()
 }
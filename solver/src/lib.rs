@@ -4,12 +4,24 @@
 pub mod utils;
 
 pub mod bitset;
+pub mod canonical;
+pub mod cnf;
+pub mod coordinate;
+pub mod daily;
 pub mod difficulty;
+pub mod game;
 pub mod generator;
 pub mod grid;
+pub mod library;
+pub mod messages;
+pub mod minizinc;
+pub mod mistakes;
 pub mod puzzle_coding;
 pub mod solve_result;
 pub mod solver;
 pub mod strategy;
 pub mod strats;
+pub mod svg;
+pub mod transform;
 pub mod validator;
+pub mod walkthrough;
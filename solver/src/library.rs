@@ -0,0 +1,326 @@
+//! Puzzle collections stored as JSON Lines
+//!
+//! A library file has one JSON object per line. Blank lines are ignored. Every entry has the fields
+//!
+//! - `puzzle`: the puzzle in the str8ts.com format (`T9B...`)
+//! - `solution`: the solved grid in the same format
+//! - `generator`: the parameters and seed for `generator_loop`, or absent for imported puzzles
+//! - `difficulty`: the `Difficulty` of the puzzle, with stars and the techniques needed
+//! - `canonical`: the canonical form, shared by every rotation, reflection and digit reversal of the puzzle
+//! - `tags`: a list of free-form strings
+//! - `author`: a string, or absent
+//!
+//! No two entries may share a canonical form.
+//!
+//! ```text
+//! {"puzzle":"T9B...","solution":"T9B...","difficulty":{"star_count":4,...},"canonical":"T9B...","tags":["weekly"],"author":"jgke"}
+//! ```
+
+use crate::canonical::canonical_form;
+use crate::difficulty::{get_puzzle_difficulty, Difficulty};
+use crate::grid::Grid;
+use crate::mistakes::{original_puzzle, solve_puzzle};
+use crate::puzzle_coding::encode;
+use crate::strategy::StrategyList;
+use rustc_hash::FxHashSet;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GeneratorParams {
+    pub size: usize,
+    pub blocker_count: usize,
+    pub blocker_num_count: usize,
+    pub target_difficulty: usize,
+    pub symmetric: bool,
+    pub seed: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibraryEntry {
+    pub puzzle: String,
+    pub solution: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generator: Option<GeneratorParams>,
+    pub difficulty: Difficulty,
+    pub canonical: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+}
+
+impl LibraryEntry {
+    /* Solves and rates the puzzle. Player progress in the grid is dropped. */
+    pub fn new(grid: &Grid) -> Result<LibraryEntry, String> {
+        let puzzle = original_puzzle(grid)?;
+        /* The solution is searched for, so the rating below is the only place strategies are chosen */
+        let solution = solve_puzzle(&puzzle).map_err(|e| e.to_string())?;
+        let difficulty = get_puzzle_difficulty(&puzzle, &StrategyList::all())
+            .ok_or_else(|| "Could not rate the puzzle".to_string())?;
+        Ok(LibraryEntry {
+            puzzle: encode(&puzzle),
+            solution: encode(&solution),
+            generator: None,
+            difficulty,
+            canonical: canonical_form(&puzzle)?,
+            tags: Vec::new(),
+            author: None,
+        })
+    }
+
+    pub fn grid(&self) -> Result<Grid, String> {
+        Grid::parse(vec![self.puzzle.clone()])
+    }
+
+    pub fn solution_grid(&self) -> Result<Grid, String> {
+        Grid::parse(vec![self.solution.clone()])
+    }
+
+    pub fn stars(&self) -> usize {
+        self.difficulty.star_count
+    }
+
+    pub fn uses_technique(&self, technique: &str) -> bool {
+        self.difficulty.techniques().contains(&technique)
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Filter {
+    pub stars: Option<usize>,
+    pub technique: Option<String>,
+    pub tag: Option<String>,
+    pub author: Option<String>,
+}
+
+impl Filter {
+    pub fn matches(&self, entry: &LibraryEntry) -> bool {
+        self.stars.is_none_or(|stars| entry.stars() == stars)
+            && self.technique.as_ref().is_none_or(|t| entry.uses_technique(t))
+            && self.tag.as_ref().is_none_or(|t| entry.has_tag(t))
+            && self.author.as_ref().is_none_or(|a| entry.author.as_ref() == Some(a))
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Library {
+    entries: Vec<LibraryEntry>,
+    /* canonical forms of the entries, so that adding a puzzle doesn't scan the whole library */
+    canonical: FxHashSet<String>,
+}
+
+impl Library {
+    /* Entries equivalent to an earlier one are dropped */
+    pub fn new(entries: Vec<LibraryEntry>) -> Library {
+        let mut library = Library::default();
+        for entry in entries {
+            library.add(entry);
+        }
+        library
+    }
+
+    pub fn entries(&self) -> &[LibraryEntry] {
+        &self.entries
+    }
+
+    pub fn into_entries(self) -> Vec<LibraryEntry> {
+        self.entries
+    }
+
+    /* Equivalent puzzles on different lines are an error, as add() would never have let them in */
+    pub fn parse(input: &str) -> Result<Library, String> {
+        let mut library = Library::default();
+        let mut lines = Vec::new();
+        for (i, line) in input.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: LibraryEntry = serde_json::from_str(line).map_err(|e| format!("Line {}: {}", i + 1, e))?;
+            if library.contains(&entry) {
+                let j = library
+                    .entries
+                    .iter()
+                    .position(|e| e.canonical == entry.canonical)
+                    .unwrap();
+                return Err(format!("Line {}: the puzzle is equivalent to the one on line {}", i + 1, lines[j]));
+            }
+            library.add(entry);
+            lines.push(i + 1);
+        }
+        Ok(library)
+    }
+
+    pub fn contains(&self, entry: &LibraryEntry) -> bool {
+        self.canonical.contains(&entry.canonical)
+    }
+
+    /* Returns false if an equivalent puzzle is already in the library */
+    pub fn add(&mut self, entry: LibraryEntry) -> bool {
+        if !self.canonical.insert(entry.canonical.clone()) {
+            return false;
+        }
+        self.entries.push(entry);
+        true
+    }
+
+    pub fn filter<'a>(&'a self, filter: &'a Filter) -> impl Iterator<Item = &'a LibraryEntry> + 'a {
+        self.entries.iter().filter(|entry| filter.matches(entry))
+    }
+
+    pub fn to_jsonl(&self) -> String {
+        self.entries
+            .iter()
+            .map(|entry| serde_json::to_string(entry).unwrap() + "\n")
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::*;
+
+    const PUZZLE: &str = "
+12#.7...6
+.#.4..3..
+.#.#i....
+7..3.....
+9#3#.#8#7
+.....1..4
+....h#.#.
+..9..8.#.
+5...4.#32
+";
+
+    #[test]
+    fn test_round_trip() {
+        let mut entry = LibraryEntry::new(&g(PUZZLE)).unwrap();
+        entry.tags = vec!["weekly".to_string()];
+        entry.author = Some("jgke".to_string());
+        entry.generator = Some(GeneratorParams {
+            size: 9,
+            blocker_count: 15,
+            blocker_num_count: 5,
+            target_difficulty: 5,
+            symmetric: true,
+            seed: 42,
+        });
+        assert_eq!(entry.grid().unwrap(), g(PUZZLE));
+        assert!(entry.solution_grid().unwrap().is_solved());
+
+        let mut library = Library::default();
+        assert!(library.add(entry.clone()));
+        let rotated = LibraryEntry::new(&g(PUZZLE).rotate(1)).unwrap();
+        assert!(!library.add(rotated));
+
+        let text = library.to_jsonl();
+        assert_eq!(text.lines().count(), 1);
+        let mut parsed = Library::parse(&format!("\n{}\n", text)).unwrap();
+        assert_eq!(parsed, library);
+        assert!(parsed.contains(&entry));
+        assert!(!parsed.add(entry));
+
+        let minimal = r#"{"puzzle":"P","solution":"S","difficulty":{"star_count":3},"canonical":"C"}"#;
+        let parsed = Library::parse(minimal).unwrap();
+        assert_eq!(parsed.entries()[0].difficulty.star_count, 3);
+        assert_eq!(parsed.entries()[0].author, None);
+        assert!(Library::parse("{").unwrap_err().starts_with("Line 1:"));
+    }
+
+    #[test]
+    fn test_filter() {
+        let entry = LibraryEntry::new(&g(PUZZLE)).unwrap();
+        let stars = entry.stars();
+        let technique = entry.difficulty.techniques()[0];
+        let library = Library::new(vec![entry]);
+
+        let count = |filter: Filter| library.filter(&filter).count();
+        assert_eq!(count(Filter::default()), 1);
+        assert_eq!(
+            count(Filter {
+                stars: Some(stars),
+                technique: Some(technique.to_string()),
+                ..Filter::default()
+            }),
+            1
+        );
+        assert_eq!(
+            count(Filter {
+                stars: Some(stars + 1),
+                ..Filter::default()
+            }),
+            0
+        );
+        assert_eq!(
+            count(Filter {
+                tag: Some("weekly".to_string()),
+                ..Filter::default()
+            }),
+            0
+        );
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let entry = LibraryEntry::new(&g(PUZZLE)).unwrap();
+        let rotated = LibraryEntry::new(&g(PUZZLE).rotate(2)).unwrap();
+        let small = LibraryEntry::new(&g("
+1.#4
+..#.
+#...
+4..#
+"))
+        .unwrap();
+
+        let library = Library::new(vec![entry.clone(), small.clone(), rotated.clone()]);
+        assert_eq!(library.entries(), &[entry.clone(), small.clone()]);
+
+        let text = [&entry, &small, &rotated]
+            .iter()
+            .map(|entry| serde_json::to_string(entry).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        assert_eq!(
+            Library::parse(&text),
+            Err("Line 5: the puzzle is equivalent to the one on line 1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_small_entry() {
+        let entry = LibraryEntry::new(&g("
+1.#4
+..#.
+#...
+4..#
+"))
+        .unwrap();
+        assert_eq!(entry.solution_grid().unwrap().to_string(), "12#4\n21#3\n#432\n432#");
+        assert!(entry.puzzle.starts_with("T4B"), "{}", entry.puzzle);
+        assert_eq!(
+            Library::parse(&Library::new(vec![entry.clone()]).to_jsonl())
+                .unwrap()
+                .entries(),
+            &[entry]
+        );
+    }
+
+    #[test]
+    fn test_invalid_puzzles() {
+        let ambiguous = LibraryEntry::new(&g("
+#4..
+..#.
+.#..
+..4#
+"));
+        assert_eq!(ambiguous, Err("Grid is ambiguous, and cannot be solved".to_string()));
+
+        let mut malformed = g(PUZZLE);
+        malformed.cells.pop();
+        assert!(LibraryEntry::new(&malformed).is_err());
+    }
+}
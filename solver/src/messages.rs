@@ -0,0 +1,482 @@
+//! Translatable step explanations and validation messages
+//!
+//! Messages are identified by a key and carry their parameters as structured data. Each locale has a
+//! catalog of templates where `{name}` is replaced with the rendered parameter of the same name.
+
+use crate::coordinate::{Coordinate, Notation};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Hash)]
+pub enum Locale {
+    #[default]
+    English,
+    Finnish,
+    German,
+}
+
+pub const LOCALES: &[Locale] = &[Locale::English, Locale::Finnish, Locale::German];
+
+impl Locale {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::Finnish => "fi",
+            Locale::German => "de",
+        }
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    /* Accepts language codes with a region, such as "fi-FI" or "de_AT" */
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let language = s.split(['-', '_']).next().unwrap_or("").to_ascii_lowercase();
+        LOCALES
+            .iter()
+            .find(|locale| locale.code() == language)
+            .copied()
+            .ok_or_else(|| format!("Unknown locale '{}'", s))
+    }
+}
+
+impl Display for Locale {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Param {
+    Number(u8),
+    Numbers(Vec<u8>),
+    Count(usize),
+    /* Zero-based row or column index */
+    Index(usize),
+    Cell(Coordinate),
+    Cells(Vec<Coordinate>),
+    /* "row" or "column" */
+    Line(bool),
+    /* "rows" or "columns" */
+    Lines(bool),
+    /* "Horizontal" or "Vertical" */
+    Direction(bool),
+}
+
+/* How messages are rendered for the user */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Hash)]
+pub struct MessageOptions {
+    pub locale: Locale,
+    pub notation: Notation,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Message {
+    pub key: &'static str,
+    pub params: Vec<(&'static str, Param)>,
+}
+
+impl Message {
+    pub fn new(key: &'static str) -> Message {
+        Message {
+            key,
+            params: Vec::new(),
+        }
+    }
+
+    pub fn with(mut self, name: &'static str, param: Param) -> Message {
+        self.params.push((name, param));
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Param> {
+        self.params.iter().find(|(n, _)| *n == name).map(|(_, param)| param)
+    }
+
+    pub fn localize(&self, locale: Locale) -> String {
+        self.format(MessageOptions {
+            locale,
+            ..MessageOptions::default()
+        })
+    }
+
+    pub fn format(&self, options: MessageOptions) -> String {
+        let MessageOptions { locale, notation } = options;
+        let template = template(locale, self.key)
+            .or_else(|| template(Locale::English, self.key))
+            .unwrap_or(self.key);
+
+        let mut out = String::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            out.push_str(&rest[..start]);
+            let Some(len) = rest[start..].find('}') else {
+                break;
+            };
+            let name = &rest[start + 1..start + len];
+            match self.get(name) {
+                Some(param) => out.push_str(&render_param(locale, notation, param)),
+                None => out.push_str(&rest[start..=start + len]),
+            }
+            rest = &rest[start + len + 1..];
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+impl Display for Message {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.localize(Locale::English))
+    }
+}
+
+fn render_list(locale: Locale, list: &[String]) -> String {
+    match list.len() {
+        0 => "".to_string(),
+        1 => list[0].to_string(),
+        _ => {
+            let (last, rest) = list.split_last().unwrap();
+            format!("{} {} {}", rest.join(", "), word(locale, "and"), last)
+        }
+    }
+}
+
+fn render_param(locale: Locale, notation: Notation, param: &Param) -> String {
+    match param {
+        Param::Number(n) => n.to_string(),
+        Param::Numbers(list) => render_list(locale, &list.iter().map(|n| n.to_string()).collect::<Vec<_>>()),
+        Param::Count(n) => n.to_string(),
+        Param::Index(index) => (index + 1).to_string(),
+        Param::Cell(pos) => pos.format(notation),
+        Param::Cells(list) => format!(
+            "[{}]",
+            list.iter()
+                .map(|pos| pos.format(notation))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Param::Line(vertical) => word(locale, if *vertical { "column" } else { "row" }).to_string(),
+        Param::Lines(vertical) => word(locale, if *vertical { "columns" } else { "rows" }).to_string(),
+        Param::Direction(vertical) => word(locale, if *vertical { "vertical" } else { "horizontal" }).to_string(),
+    }
+}
+
+fn word(locale: Locale, word: &'static str) -> &'static str {
+    match (locale, word) {
+        (Locale::English, "and") => "and",
+        (Locale::English, "row") => "row",
+        (Locale::English, "column") => "column",
+        (Locale::English, "rows") => "rows",
+        (Locale::English, "columns") => "columns",
+        (Locale::English, "horizontal") => "Horizontal",
+        (Locale::English, "vertical") => "Vertical",
+
+        (Locale::Finnish, "and") => "ja",
+        (Locale::Finnish, "row") => "rivi",
+        (Locale::Finnish, "column") => "sarake",
+        /* Only used in the inessive case, "riveissä" */
+        (Locale::Finnish, "rows") => "riveissä",
+        (Locale::Finnish, "columns") => "sarakkeissa",
+        (Locale::Finnish, "horizontal") => "Vaakasuuntainen",
+        (Locale::Finnish, "vertical") => "Pystysuuntainen",
+
+        (Locale::German, "and") => "und",
+        (Locale::German, "row") => "Zeile",
+        (Locale::German, "column") => "Spalte",
+        (Locale::German, "rows") => "Zeilen",
+        (Locale::German, "columns") => "Spalten",
+        (Locale::German, "horizontal") => "waagerechte",
+        (Locale::German, "vertical") => "senkrechte",
+
+        (_, word) => word,
+    }
+}
+
+fn template(locale: Locale, key: &str) -> Option<&'static str> {
+    match locale {
+        Locale::English => english(key),
+        Locale::Finnish => finnish(key),
+        Locale::German => german(key),
+    }
+}
+
+fn english(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "update_impossibles" => "Remove trivially impossible numbers",
+        "stranded" => "Remove stranded numbers",
+        "definite_min_max" => "Remove unreachable numbers from compartments",
+        "singles" => "Find hidden singles",
+        "required_range" => "Remove numbers from other compartments if they are required in others",
+        "sets" => "Find out sets of {n} numbers",
+        "required_and_forbidden" => "List required numbers and blocked numbers",
+        "hidden_sets" => "The numbers {numbers} fit only in {cells}, so nothing else can go there",
+        "row_col_brute" => "Think very hard about possible combinations in rows and columns",
+        "setti" => "Calculate settis on {numbers}",
+        "y_wing" => "Y-Wing causes {cell} to not be {n}",
+        "x_wing" => "Calculate a X-wing",
+        "swordfish" => "Calculate a Swordfish",
+        "fish" => "Calculate a {n}-fish",
+        "xyz_wing" => "XYZ-Wing on {cell} removes {count} candidates",
+        "x_chain" => "Coloring the strong links of {n} shows that {cells} cannot be {n}",
+        "finned_fish" => "Finned {size}-fish on {n} shows that {cells} cannot be {n}",
+        "sashimi_fish" => "Sashimi {size}-fish on {n} shows that {cells} cannot be {n}",
+        "xy_chain" => "XY-Chain through {cells} removes {count} candidates",
+        "medusa" => "Calculate a 3D Medusa",
+        "aic" => "Alternating inference chain through {cells} removes {count} candidates",
+        "ur_single_unique" => "{cell} must be {n}, as other solutions would be ambiguous",
+        "ur_intra_compartment_unique" => "{cell} cannot be {n}, as it would cause ambiguous solutions",
+        "ur_closed_set_compartment" => "The cells {cells} must contain {n} or the puzzle becomes ambiguous",
+        "ur_single_cell_would_become_free" => "{cell} cannot be {n}, as it would cause ambiguous solutions",
+        "ur_setti" => "The {lines} containing points {cells} must contain {n}, or the puzzle becomes ambiguous",
+        "ur_solution_causes_closed_sets" => {
+            "Setting {cell} to {n} creates closed sets, causing puzzle to become ambiguous"
+        }
+        "pattern_overlay" => "No placement pattern of {n} uses {cells}",
+        "pattern_overlay_place" => "Every placement pattern of {n} uses {cells}",
+        "cell_forcing_chain" => {
+            "Every number in {cell} leads to removing the same {count} candidates and placing the same {placed} numbers"
+        }
+        "unit_forcing_chain" => {
+            "Every place for {n} in {cells} leads to removing the same {count} candidates and placing the same {placed} numbers"
+        }
+        "start_guess" => "Start guess with {cell} = {n}",
+        "guess_step" => "{cell} cannot be {n}, as it causes a conflict in {count} steps",
+        "puzzle_solved" => "Puzzle solved",
+        "enumerate_solutions" => "Enumerate all possible solutions",
+
+        "empty_cell" => "Cell scan: Cell {cell} ran out of possible options",
+        "conflict" => "Cells {cell1} and {cell2} both contain {n}",
+        "sequence" => "Compartment scan: {direction} compartment starting from {cell} contains numbers {min} and {max}, but it doesn't contain {missing} making it non-contiguous",
+        "sequence_too_large" => "Compartment scan: {direction} compartment starting from {cell} contains numbers {a} and {b}, but it's too small for them as it can either contain {min_a} to {max_a} or {min_b} to {max_b}",
+        "requirement_blocker_conflict" => {
+            "The number {n} is included both in required and blocked numbers for {line} {index}"
+        }
+        "required_number_missing" => "The number {n} is required in {line} {index} but not present in any container",
+        "blocked_number_present" => "The number {n} is forbidden in {line} {index} but is a solution or a requirement",
+        "ambiguous" => "Grid is ambiguous, and cannot be solved",
+        "no_solutions" => "Exhaustive search proves grid has no solutions",
+        "out_of_strats" => "Ran out of strategies!",
+        _ => return None,
+    })
+}
+
+fn finnish(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "update_impossibles" => "Poista selvästi mahdottomat numerot",
+        "stranded" => "Poista eristyneet numerot",
+        "definite_min_max" => "Poista lokeroista saavuttamattomat numerot",
+        "singles" => "Etsi piilotetut yksinäiset numerot",
+        "required_range" => "Poista numerot muista lokeroista, jos ne vaaditaan toisessa",
+        "sets" => "Etsi {n} numeron joukot",
+        "required_and_forbidden" => "Listaa vaaditut ja estetyt numerot",
+        "hidden_sets" => "Numerot {numbers} mahtuvat vain soluihin {cells}, joten niihin ei voi tulla muuta",
+        "row_col_brute" => "Mieti tarkkaan rivien ja sarakkeiden mahdollisia yhdistelmiä",
+        "setti" => "Laske setit numeroille {numbers}",
+        "y_wing" => "Y-Wing estää solua {cell} olemasta {n}",
+        "x_wing" => "Laske X-wing",
+        "swordfish" => "Laske Swordfish",
+        "fish" => "Laske {n}-kala",
+        "xyz_wing" => "XYZ-Wing solussa {cell} poistaa {count} vaihtoehtoa",
+        "xy_chain" => "XY-ketju solujen {cells} kautta poistaa {count} vaihtoehtoa",
+        "x_chain" => "Numeron {n} vahvojen linkkien väritys osoittaa, etteivät solut {cells} voi olla {n}",
+        "finned_fish" => "Numeron {n} evällinen {size}-kala osoittaa, etteivät solut {cells} voi olla {n}",
+        "sashimi_fish" => "Numeron {n} sashimi-{size}-kala osoittaa, etteivät solut {cells} voi olla {n}",
+        "medusa" => "Laske 3D-Medusa",
+        "aic" => "Vuorotteleva päättelyketju solujen {cells} kautta poistaa {count} vaihtoehtoa",
+        "ur_single_unique" => "Solun {cell} on oltava {n}, koska muut ratkaisut olisivat moniselitteisiä",
+        "ur_intra_compartment_unique" => "Solu {cell} ei voi olla {n}, koska se johtaisi moniselitteisiin ratkaisuihin",
+        "ur_closed_set_compartment" => "Solujen {cells} on sisällettävä {n}, tai ratkaisusta tulee moniselitteinen",
+        "ur_single_cell_would_become_free" => {
+            "Solu {cell} ei voi olla {n}, koska se johtaisi moniselitteisiin ratkaisuihin"
+        }
+        "ur_setti" => "Pisteet {cells} sisältävissä {lines} on oltava {n}, tai ratkaisusta tulee moniselitteinen",
+        "ur_solution_causes_closed_sets" => {
+            "Numeron {n} asettaminen soluun {cell} luo suljettuja joukkoja, jolloin ratkaisusta tulee moniselitteinen"
+        }
+        "pattern_overlay" => "Mikään numeron {n} sijoittelukuvio ei käytä soluja {cells}",
+        "pattern_overlay_place" => "Jokainen numeron {n} sijoittelukuvio käyttää soluja {cells}",
+        "cell_forcing_chain" => {
+            "Jokainen solun {cell} numero johtaa samojen {count} vaihtoehdon poistoon ja samojen {placed} numeron asettamiseen"
+        }
+        "unit_forcing_chain" => {
+            "Jokainen numeron {n} paikka soluissa {cells} johtaa samojen {count} vaihtoehdon poistoon ja samojen {placed} numeron asettamiseen"
+        }
+        "start_guess" => "Aloita arvaus: {cell} = {n}",
+        "guess_step" => "Solu {cell} ei voi olla {n}, koska se johtaa ristiriitaan {count} askeleessa",
+        "puzzle_solved" => "Ristikko ratkaistu",
+        "enumerate_solutions" => "Käy läpi kaikki mahdolliset ratkaisut",
+
+        "empty_cell" => "Solutarkistus: solulta {cell} loppuivat vaihtoehdot",
+        "conflict" => "Solut {cell1} ja {cell2} sisältävät molemmat numeron {n}",
+        "sequence" => "Lokerotarkistus: {direction} lokero alkaen solusta {cell} sisältää numerot {min} ja {max}, mutta ei numeroa {missing}, joten se ei ole yhtenäinen",
+        "sequence_too_large" => "Lokerotarkistus: {direction} lokero alkaen solusta {cell} sisältää numerot {a} ja {b}, mutta se on niille liian pieni, koska siinä voi olla joko numerot {min_a}–{max_a} tai {min_b}–{max_b}",
+        "requirement_blocker_conflict" => "Numero {n} on sekä vaadittu että estetty: {line} {index}",
+        "required_number_missing" => "Numero {n} vaaditaan ({line} {index}), mutta sitä ei ole yhdessäkään lokerossa",
+        "blocked_number_present" => "Numero {n} on kielletty ({line} {index}), mutta se on ratkaisu tai vaatimus",
+        "ambiguous" => "Ristikolla on useita ratkaisuja, eikä sitä voi ratkaista",
+        "no_solutions" => "Kattava haku osoittaa, ettei ristikolla ole ratkaisuja",
+        "out_of_strats" => "Strategiat loppuivat!",
+        _ => return None,
+    })
+}
+
+fn german(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "update_impossibles" => "Offensichtlich unmögliche Zahlen entfernen",
+        "stranded" => "Isolierte Zahlen entfernen",
+        "definite_min_max" => "Unerreichbare Zahlen aus Abschnitten entfernen",
+        "singles" => "Versteckte Singles finden",
+        "required_range" => "Zahlen aus anderen Abschnitten entfernen, wenn sie in einem anderen benötigt werden",
+        "sets" => "Mengen von {n} Zahlen finden",
+        "required_and_forbidden" => "Benötigte und blockierte Zahlen auflisten",
+        "hidden_sets" => "Die Zahlen {numbers} passen nur in {cells}, also kann dort nichts anderes stehen",
+        "row_col_brute" => "Gründlich über mögliche Kombinationen in Zeilen und Spalten nachdenken",
+        "setti" => "Settis für {numbers} berechnen",
+        "y_wing" => "Y-Wing verhindert, dass {cell} {n} ist",
+        "x_wing" => "X-Wing berechnen",
+        "swordfish" => "Swordfish berechnen",
+        "fish" => "{n}-Fisch berechnen",
+        "xyz_wing" => "XYZ-Wing auf {cell} entfernt {count} Kandidaten",
+        "xy_chain" => "XY-Kette durch {cells} entfernt {count} Kandidaten",
+        "x_chain" => "Das Färben der starken Verbindungen von {n} zeigt, dass {cells} nicht {n} sein können",
+        "finned_fish" => "Ein {size}-Fisch mit Flosse auf {n} zeigt, dass {cells} nicht {n} sein können",
+        "sashimi_fish" => "Ein Sashimi-{size}-Fisch auf {n} zeigt, dass {cells} nicht {n} sein können",
+        "medusa" => "3D-Medusa berechnen",
+        "aic" => "Alternierende Inferenzkette durch {cells} entfernt {count} Kandidaten",
+        "ur_single_unique" => "{cell} muss {n} sein, da andere Lösungen mehrdeutig wären",
+        "ur_intra_compartment_unique" => "{cell} kann nicht {n} sein, da dies zu mehrdeutigen Lösungen führen würde",
+        "ur_closed_set_compartment" => "Die Zellen {cells} müssen {n} enthalten, sonst wird das Rätsel mehrdeutig",
+        "ur_single_cell_would_become_free" => {
+            "{cell} kann nicht {n} sein, da dies zu mehrdeutigen Lösungen führen würde"
+        }
+        "ur_setti" => "Die {lines} mit den Punkten {cells} müssen {n} enthalten, sonst wird das Rätsel mehrdeutig",
+        "ur_solution_causes_closed_sets" => {
+            "{n} in {cell} zu setzen erzeugt geschlossene Mengen, wodurch das Rätsel mehrdeutig wird"
+        }
+        "pattern_overlay" => "Kein Platzierungsmuster von {n} verwendet {cells}",
+        "pattern_overlay_place" => "Jedes Platzierungsmuster von {n} verwendet {cells}",
+        "cell_forcing_chain" => "Jede Zahl in {cell} entfernt dieselben {count} Kandidaten und setzt dieselben {placed} Zahlen",
+        "unit_forcing_chain" => {
+            "Jede Position von {n} in {cells} entfernt dieselben {count} Kandidaten und setzt dieselben {placed} Zahlen"
+        }
+        "start_guess" => "Vermutung beginnen mit {cell} = {n}",
+        "guess_step" => "{cell} kann nicht {n} sein, da dies in {count} Schritten zu einem Widerspruch führt",
+        "puzzle_solved" => "Rätsel gelöst",
+        "enumerate_solutions" => "Alle möglichen Lösungen aufzählen",
+
+        "empty_cell" => "Zellprüfung: Zelle {cell} hat keine möglichen Zahlen mehr",
+        "conflict" => "Die Zellen {cell1} und {cell2} enthalten beide {n}",
+        "sequence" => "Abschnittsprüfung: Der {direction} Abschnitt ab {cell} enthält die Zahlen {min} und {max}, aber nicht {missing}, und ist daher nicht zusammenhängend",
+        "sequence_too_large" => "Abschnittsprüfung: Der {direction} Abschnitt ab {cell} enthält die Zahlen {a} und {b}, ist dafür aber zu klein, da er entweder {min_a} bis {max_a} oder {min_b} bis {max_b} enthalten kann",
+        "requirement_blocker_conflict" => "Die Zahl {n} ist für {line} {index} sowohl benötigt als auch blockiert",
+        "required_number_missing" => {
+            "Die Zahl {n} wird in {line} {index} benötigt, ist aber in keinem Abschnitt vorhanden"
+        }
+        "blocked_number_present" => "Die Zahl {n} ist in {line} {index} verboten, ist aber eine Lösung oder Vorgabe",
+        "ambiguous" => "Das Gitter ist mehrdeutig und kann nicht gelöst werden",
+        "no_solutions" => "Eine vollständige Suche beweist, dass das Gitter keine Lösungen hat",
+        "out_of_strats" => "Keine Strategien mehr übrig!",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale() {
+        assert_eq!("fi".parse(), Ok(Locale::Finnish));
+        assert_eq!("de_AT".parse(), Ok(Locale::German));
+        assert_eq!("EN-us".parse(), Ok(Locale::English));
+        assert!("xx".parse::<Locale>().is_err());
+    }
+
+    #[test]
+    fn test_localize() {
+        let msg = Message::new("setti").with("numbers", Param::Numbers(vec![1, 2, 3]));
+        assert_eq!(msg.localize(Locale::English), "Calculate settis on 1, 2 and 3");
+        assert_eq!(msg.localize(Locale::Finnish), "Laske setit numeroille 1, 2 ja 3");
+        assert_eq!(msg.localize(Locale::German), "Settis für 1, 2 und 3 berechnen");
+
+        let msg = Message::new("required_number_missing")
+            .with("n", Param::Number(4))
+            .with("line", Param::Line(true))
+            .with("index", Param::Index(2));
+        assert_eq!(
+            msg.localize(Locale::German),
+            "Die Zahl 4 wird in Spalte 3 benötigt, ist aber in keinem Abschnitt vorhanden"
+        );
+
+        let msg = Message::new("conflict")
+            .with("cell1", Param::Cell(Coordinate((0, 1))))
+            .with("cell2", Param::Cell(Coordinate((0, 3))))
+            .with("n", Param::Number(5));
+        let options = MessageOptions {
+            locale: Locale::Finnish,
+            notation: Notation::Chess,
+        };
+        assert_eq!(msg.format(options), "Solut A2 ja A4 sisältävät molemmat numeron 5");
+
+        assert_eq!(Message::new("y_wing").localize(Locale::English), "Y-Wing causes {cell} to not be {n}");
+        assert_eq!(Message::new("unknown").localize(Locale::Finnish), "unknown");
+    }
+
+    #[test]
+    fn test_catalogs_are_complete() {
+        let keys = [
+            "update_impossibles",
+            "stranded",
+            "definite_min_max",
+            "singles",
+            "required_range",
+            "sets",
+            "required_and_forbidden",
+            "hidden_sets",
+            "row_col_brute",
+            "setti",
+            "y_wing",
+            "x_wing",
+            "swordfish",
+            "fish",
+            "xyz_wing",
+            "x_chain",
+            "finned_fish",
+            "sashimi_fish",
+            "xy_chain",
+            "medusa",
+            "aic",
+            "ur_single_unique",
+            "ur_intra_compartment_unique",
+            "ur_closed_set_compartment",
+            "ur_single_cell_would_become_free",
+            "ur_setti",
+            "ur_solution_causes_closed_sets",
+            "pattern_overlay",
+            "pattern_overlay_place",
+            "cell_forcing_chain",
+            "unit_forcing_chain",
+            "start_guess",
+            "guess_step",
+            "puzzle_solved",
+            "enumerate_solutions",
+            "empty_cell",
+            "conflict",
+            "sequence",
+            "sequence_too_large",
+            "requirement_blocker_conflict",
+            "required_number_missing",
+            "blocked_number_present",
+            "ambiguous",
+            "no_solutions",
+            "out_of_strats",
+        ];
+        for locale in LOCALES {
+            for key in keys {
+                assert!(template(*locale, key).is_some(), "{} is missing {}", locale, key);
+            }
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! The puzzle as a MiniZinc model, for cross-checking with constraint solvers
+//!
+//! `x[row, col]` holds the number of each cell, with 0 in black cells. The model prints the solved grid as
+//! rows of numbers, which `decode_output` reads back into a `Grid`.
+
+use crate::cnf::apply_values;
+use crate::grid::Cell::*;
+use crate::grid::{Grid, Point};
+use itertools::Itertools;
+use std::fmt::Write;
+
+fn var((x, y): Point) -> String {
+    format!("x[{}, {}]", y + 1, x + 1)
+}
+
+pub fn to_minizinc(grid: &Grid) -> String {
+    let size = grid.x;
+    let mut out = String::new();
+    writeln!(out, "% str8ts puzzle").unwrap();
+    writeln!(out, "include \"alldifferent.mzn\";").unwrap();
+    writeln!(out, "int: n = {};", size).unwrap();
+    writeln!(out, "array[1..n, 1..n] of var 0..n: x;").unwrap();
+    writeln!(out).unwrap();
+
+    writeln!(out, "% cell domains").unwrap();
+    for (pos, cell) in grid.iter_by_cells() {
+        match cell {
+            Requirement(n) | Solution(n) => writeln!(out, "constraint {} = {};", var(pos), n),
+            Indeterminate(set) => writeln!(out, "constraint {} in {{{}}};", var(pos), set.into_iter().join(", ")),
+            Blocker(_) | Black => writeln!(out, "constraint {} = 0;", var(pos)),
+        }
+        .unwrap();
+    }
+
+    writeln!(out, "\n% rows and columns").unwrap();
+    for (vertical, line) in grid.iter_by_rows_and_cols() {
+        let index = if vertical { line[0].0 .0 } else { line[0].0 .1 };
+        let white = line
+            .iter()
+            .filter(|(_, cell)| cell.is_compartment_cell())
+            .map(|(pos, _)| var(*pos))
+            .collect::<Vec<_>>();
+        if white.is_empty() {
+            continue;
+        }
+        writeln!(out, "constraint alldifferent([{}]);", white.join(", ")).unwrap();
+        let blocked = line
+            .iter()
+            .filter_map(|(_, cell)| if let Blocker(n) = cell { Some(*n) } else { None })
+            .chain(grid.forbidden(vertical, (index, index)))
+            .sorted()
+            .dedup()
+            .collect::<Vec<_>>();
+        for n in blocked {
+            writeln!(out, "constraint forall(v in [{}])(v != {});", white.join(", "), n).unwrap();
+        }
+        for n in grid.requirements(vertical, (index, index)) {
+            writeln!(out, "constraint exists(v in [{}])(v = {});", white.join(", "), n).unwrap();
+        }
+    }
+
+    /* distinct numbers spanning len - 1 form a straight */
+    writeln!(out, "\n% compartments").unwrap();
+    for compartment in grid.iter_by_compartments() {
+        let cells = compartment.cells.iter().map(|(pos, _)| var(*pos)).join(", ");
+        writeln!(out, "constraint max([{}]) - min([{}]) = {};", cells, cells, compartment.cells.len() - 1).unwrap();
+    }
+
+    writeln!(out, "\nsolve satisfy;").unwrap();
+    writeln!(
+        out,
+        "output [show(x[r, c]) ++ if c = n then \"\\n\" else \" \" endif | r in 1..n, c in 1..n];"
+    )
+    .unwrap();
+    out
+}
+
+/* Reads the first solution printed by the model */
+pub fn decode_output(grid: &Grid, output: &str) -> Result<Grid, String> {
+    if output.contains("=====UNSATISFIABLE=====") {
+        return Err("The puzzle has no solutions".to_string());
+    }
+    let values = output
+        .lines()
+        .take_while(|line| !line.starts_with("----------"))
+        .flat_map(|line| line.split_whitespace())
+        .map(|token| {
+            token
+                .parse::<u8>()
+                .map_err(|_| format!("Unexpected '{}' in the solution", token))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    apply_values(grid, &values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mistakes::solve_puzzle;
+    use crate::utils::*;
+    use std::process::Command;
+
+    const PUZZLE: &str = "
+32.d.
+#1##3
+.432.
+a....
+.....
+";
+
+    #[test]
+    fn test_model() {
+        let grid = g(PUZZLE);
+        let model = to_minizinc(&grid);
+        assert!(model.contains("constraint x[1, 1] = 3;"));
+        assert!(model.contains("constraint x[2, 1] = 0;"));
+        assert!(model.contains("constraint x[1, 3] in {1, 2, 3, 4, 5};"));
+        assert!(model.contains("constraint alldifferent([x[4, 2], x[4, 3], x[4, 4], x[4, 5]]);"));
+        assert!(model.contains("constraint forall(v in [x[4, 2], x[4, 3], x[4, 4], x[4, 5]])(v != 1);"));
+        assert!(model.contains("constraint max([x[2, 2]]) - min([x[2, 2]]) = 0;"));
+
+        let solution = solve_puzzle(&grid).unwrap();
+        let output = solution
+            .cells
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.to_req_or_sol().unwrap_or(0)).join(" "))
+            .join("\n")
+            + "\n----------\n";
+        assert_eq!(decode_output(&grid, &output).unwrap().cells, solution.cells);
+        assert!(decode_output(&grid, "=====UNSATISFIABLE=====").is_err());
+        assert!(decode_output(&grid, "1 2 3\n----------").is_err());
+    }
+
+    /* Needs minizinc on the PATH, run with `cargo test -p solver -- --ignored test_minizinc` */
+    #[test]
+    #[ignore]
+    fn test_minizinc() {
+        let grid = g(PUZZLE);
+        let path = std::env::temp_dir().join(format!("str8ts-{}.mzn", std::process::id()));
+        std::fs::write(&path, to_minizinc(&grid)).unwrap();
+        let output = Command::new("minizinc").arg(&path).output();
+        std::fs::remove_file(&path).unwrap();
+        let output = output.expect("failed to run minizinc");
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let output = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(decode_output(&grid, &output).unwrap().cells, solve_puzzle(&grid).unwrap().cells);
+    }
+}
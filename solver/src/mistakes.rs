@@ -0,0 +1,153 @@
+use crate::grid::Cell::*;
+use crate::grid::{Grid, Point};
+use crate::solve_result::ValidationResult;
+use crate::strats::search_solutions;
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Mistakes {
+    /* (position, entered number, correct number) */
+    pub wrong_entries: Vec<(Point, u8, u8)>,
+    /* (position, correct number) for cells whose candidates no longer contain the solution */
+    pub removed_solutions: Vec<(Point, u8)>,
+}
+
+impl Mistakes {
+    pub fn is_empty(&self) -> bool {
+        self.wrong_entries.is_empty() && self.removed_solutions.is_empty()
+    }
+}
+
+/* Strip all player progress from the grid, leaving only the original puzzle */
+pub fn original_puzzle(grid: &Grid) -> Result<Grid, String> {
+    let size = grid.x as u8;
+    let cells = grid
+        .cells
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|cell| match cell {
+                    Solution(_) | Indeterminate(_) => Indeterminate((1..=size).collect()),
+                    other => other.clone(),
+                })
+                .collect()
+        })
+        .collect();
+    Grid::new(cells)
+}
+
+/* Search for the solution instead of deducing it, as the uniqueness strategies would settle an ambiguous puzzle
+ * on one of its solutions, and mistakes can only be judged against the only one */
+pub fn solve_puzzle(puzzle: &Grid) -> Result<Grid, ValidationResult> {
+    let mut grid = puzzle.clone();
+    search_solutions(&mut grid)?;
+    Ok(grid)
+}
+
+pub fn find_mistakes_with_solution(grid: &Grid, solution: &Grid) -> Mistakes {
+    let mut mistakes = Mistakes::default();
+    for (pos, cell) in grid.iter_by_cells() {
+        let Some(correct) = solution.get_cell(pos).to_req_or_sol() else {
+            continue;
+        };
+        match cell {
+            Solution(n) if n != correct => mistakes.wrong_entries.push((pos, n, correct)),
+            Indeterminate(set) if !set.contains(correct) => mistakes.removed_solutions.push((pos, correct)),
+            _ => {}
+        }
+    }
+    mistakes
+}
+
+pub fn find_mistakes(grid: &Grid) -> Result<Mistakes, String> {
+    let solution = solve_puzzle(&original_puzzle(grid)?).map_err(|e| e.to_string())?;
+    Ok(find_mistakes_with_solution(grid, &solution))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solve_result::ValidationError;
+    use crate::utils::*;
+
+    const PUZZLE: &str = "
+12#.7...6
+.#.4..3..
+.#.#i....
+7..3.....
+9#3#.#8#7
+.....1..4
+....h#.#.
+..9..8.#.
+5...4.#32
+";
+
+    #[test]
+    fn test_solve_puzzle() {
+        let grid = g(PUZZLE);
+        assert_eq!(
+            solve_puzzle(&grid).unwrap().to_string(),
+            "
+12#573486
+2#7465398
+8#2#i4675
+784312569
+9#3#2#8#7
+675831924
+4567h#2#3
+3496587#1
+568947#32"
+                .trim()
+        );
+    }
+
+    #[test]
+    fn test_no_mistakes() {
+        let mut grid = g(PUZZLE);
+        grid.set_cell((3, 0), Solution(5));
+        grid.set_cell((5, 0), det([1, 3, 9]));
+
+        assert_eq!(find_mistakes(&grid), Ok(Mistakes::default()));
+    }
+
+    #[test]
+    fn test_mistakes() {
+        let mut grid = g(PUZZLE);
+        grid.set_cell((3, 0), Solution(5));
+        grid.set_cell((6, 0), Solution(3));
+        grid.set_cell((5, 0), det([1, 4]));
+        grid.set_cell((0, 1), Solution(2));
+
+        let mistakes = find_mistakes(&grid).unwrap();
+        assert_eq!(mistakes.wrong_entries, vec![((6, 0), 3, 4)]);
+        assert_eq!(mistakes.removed_solutions, vec![((5, 0), 3)]);
+        assert!(!mistakes.is_empty());
+    }
+
+    #[test]
+    fn test_original_puzzle() {
+        let mut grid = g(PUZZLE);
+        grid.set_cell((3, 0), Solution(5));
+        grid.set_cell((5, 0), det([1, 3, 9]));
+        assert_eq!(original_puzzle(&grid), Ok(g(PUZZLE)));
+
+        grid.cells.pop();
+        assert!(original_puzzle(&grid).is_err());
+        assert!(find_mistakes(&grid).is_err());
+    }
+
+    #[test]
+    fn test_ambiguous() {
+        /* The uniqueness strategies would pick one of the two solutions */
+        let grid = g("
+#4..
+..#.
+.#..
+..4#
+");
+        let Err(err) = solve_puzzle(&grid) else {
+            panic!("expected the puzzle to be ambiguous")
+        };
+        assert!(matches!(err.ty, ValidationError::Ambiguous { .. }));
+        assert_eq!(find_mistakes(&grid), Err(err.to_string()));
+    }
+}
@@ -78,7 +78,8 @@ fn parse_format_2(puzzle: Vec<char>) -> Result<Grid, String> {
                 Black
             } else if n < 20 {
                 Blocker((n - 10) as u8)
-            } else if n < 29 {
+            } else if n <= 29 {
+                /* 29 would also be an empty set of candidates, but Solution(9) is the one that can occur */
                 Solution((n - 20) as u8)
             } else {
                 Indeterminate(BitSet::new_from_number((n - 29) << 1))
@@ -138,6 +139,7 @@ pub fn parse(puzzle: Vec<String>) -> Result<Grid, String> {
     }
 }
 
+/* Format 2 (`T9B...`), which keeps solutions and candidates as well */
 pub fn encode(grid: &Grid) -> String {
     let format = 'T'.to_string();
     let size = match grid.x {
@@ -169,10 +171,39 @@ pub fn encode(grid: &Grid) -> String {
     format!("{}{}{}{}", format, size, version, cells)
 }
 
+/* Format 1: a digit for every cell followed by a black flag for every cell. Only the givens fit, so solutions
+ * and candidates are dropped. */
+pub fn encode_format_1(grid: &Grid) -> Result<String, String> {
+    if grid.x > 9 {
+        return Err(format!("Format 1 only fits puzzles up to 9x9, got {}x{}", grid.x, grid.y));
+    }
+    let cells = grid.iter_by_cells();
+    let digits = cells.iter().map(|(_, cell)| match cell {
+        Requirement(n) | Blocker(n) => (b'0' + n) as char,
+        _ => '0',
+    });
+    let flags = cells.iter().map(|(_, cell)| match cell {
+        Blocker(_) | Black => '1',
+        _ => '0',
+    });
+    Ok(digits.chain(flags).collect())
+}
+
+pub const DEFAULT_SHARE_BASE: &str = "https://jgke.fi/games/str8ts-solver";
+
+/* A link which opens the grid, including the player's progress, in the solver at `base` */
+pub fn share_url(base: &str, grid: &Grid) -> String {
+    let separator = if base.contains('?') { '&' } else { '?' };
+    format!("{}{}bd={}", base, separator, encode(grid))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mistakes::original_puzzle;
     use crate::utils::{det, g};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
 
     #[test]
     fn test_parse_url() {
@@ -195,6 +226,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_solved_nine_round_trip() {
+        let mut grid = g("
+.........
+.........
+.........
+.........
+.........
+.........
+.........
+.........
+.........
+");
+        grid.cells[0][0] = Solution(9);
+        grid.cells[8][8] = Solution(1);
+        assert_eq!(parse(vec![encode(&grid)]).unwrap(), grid);
+    }
+
     #[test]
     fn test_encode() {
         let mut grid = g("
@@ -207,5 +256,58 @@ mod tests {
         let _ = crate::strats::trivial(&mut grid);
 
         assert_eq!("T3B0a0l0d0w100a0a1002", encode(&grid));
+        assert_eq!("003000002101001100", encode_format_1(&grid).unwrap());
+        assert_eq!(
+            share_url(DEFAULT_SHARE_BASE, &grid),
+            "https://jgke.fi/games/str8ts-solver?bd=T3B0a0l0d0w100a0a1002"
+        );
+        assert_eq!(
+            share_url("http://localhost:8080/?lang=fi", &grid),
+            "http://localhost:8080/?lang=fi&bd=T3B0a0l0d0w100a0a1002"
+        );
+    }
+
+    fn random_grid(rng: &mut ChaCha8Rng) -> Grid {
+        let size = rng.random_range(1..=9);
+        let cells = (0..size)
+            .map(|_| {
+                (0..size)
+                    .map(|_| {
+                        let n = rng.random_range(1..=size as u8);
+                        match rng.random_range(0..5) {
+                            0 => Requirement(n),
+                            1 => Solution(n),
+                            2 => Blocker(n),
+                            3 => Black,
+                            _ => Indeterminate((1..=size as u8).filter(|&m| m == n || rng.random_bool(0.5)).collect()),
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Grid::new(cells).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..500 {
+            let grid = random_grid(&mut rng);
+            assert_eq!(parse(vec![encode(&grid)]).unwrap(), grid);
+            assert_eq!(parse(vec![share_url(DEFAULT_SHARE_BASE, &grid)]).unwrap(), grid);
+            let format_1 = encode_format_1(&grid).unwrap();
+            assert_eq!(parse(vec![format_1.clone()]).unwrap(), original_puzzle(&grid).unwrap());
+
+            /* and the other way around, from any valid format 1 string */
+            let size = grid.x;
+            let format_1 = (0..2 * size * size)
+                .map(|i| match i < size * size {
+                    true => char::from_digit(rng.random_range(0..=size as u32), 10).unwrap(),
+                    false if rng.random_bool(0.3) => '1',
+                    false => '0',
+                })
+                .collect::<String>();
+            assert_eq!(encode_format_1(&parse(vec![format_1.clone()]).unwrap()).unwrap(), format_1);
+        }
     }
 }
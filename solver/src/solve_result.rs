@@ -1,16 +1,27 @@
 use crate::bitset::BitSet;
 use crate::grid::{Grid, Point};
+use crate::messages::{Message, Param};
 use crate::solve_result::SolveType::*;
 use crate::solve_result::ValidationError::*;
 use crate::strategy::Strategy;
-use crate::strats::UrResult;
-use itertools::intersperse;
+use crate::strats::{Candidate, UrResult};
 use std::fmt::{Display, Formatter};
 use std::rc::Rc;
 
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
 pub struct SolveMetadata {
     pub colors: Vec<Vec<(Point, u8)>>,
+    /* groups of rows (false) and columns (true) by index, eg. the base and cover lines of a fish */
+    pub lines: Vec<Vec<(bool, usize)>>,
+}
+
+/* The color of a candidate, by the first color group which contains it. Groups past the end of the palette
+ * reuse its colors. */
+pub fn highlight<T: Copy>(colors: &[Vec<(Point, u8)>], palette: &[T], pos: Point, num: u8) -> Option<T> {
+    colors
+        .iter()
+        .position(|group| group.contains(&(pos, num)))
+        .map(|i| palette[i % palette.len()])
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -22,12 +33,20 @@ pub enum SolveType {
     RequiredRange,
     Sets(usize),
     RequiredAndForbidden,
+    HiddenSets(Vec<Point>, BitSet),
     RowColBrute,
     Setti(BitSet),
     YWing(Point, u8),
+    XyzWing(Point, Vec<Candidate>),
     Fish(usize),
+    XChain(u8, Vec<Point>),
+    FinnedFish(usize, bool, u8, Vec<Point>),
+    XyChain(Vec<Point>, Vec<Candidate>),
     Medusa,
+    Aic(Vec<Candidate>, Vec<Candidate>),
     UniqueRequirement(UrResult),
+    PatternOverlay(u8, Vec<Point>, Vec<Point>),
+    ForcingChain(Vec<Candidate>, Vec<Candidate>, Vec<Candidate>),
     StartGuess(Point, u8),
     GuessStep(Point, u8, Rc<Vec<(Grid, SolveResults)>>, Grid),
     EndGuess(ValidationResult),
@@ -58,12 +77,20 @@ impl From<SolveType> for Strategy {
             RequiredRange => Strategy::RequiredRange,
             Sets(_) => Strategy::Sets,
             RequiredAndForbidden => Strategy::RequiredAndForbidden,
+            HiddenSets(_, _) => Strategy::HiddenSets,
             RowColBrute => Strategy::RowColBrute,
             Setti(_) => Strategy::Setti,
             YWing(_, _) => Strategy::YWing,
             Fish(_) => Strategy::Fish,
+            XyzWing(_, _) => Strategy::XyzWing,
+            XChain(_, _) => Strategy::XChain,
+            FinnedFish(..) => Strategy::FinnedFish,
+            XyChain(_, _) => Strategy::XyChain,
             Medusa => Strategy::Medusa,
+            Aic(_, _) => Strategy::Aic,
             UniqueRequirement(_) => Strategy::UniqueRequirement,
+            PatternOverlay(..) => Strategy::PatternOverlay,
+            ForcingChain(_, _, _) => Strategy::ForcingChain,
             StartGuess(_, _) => Strategy::Guess,
             GuessStep(_, _, _, _) => Strategy::Guess,
             EndGuess(_) => Strategy::Guess,
@@ -74,93 +101,94 @@ impl From<SolveType> for Strategy {
     }
 }
 
-fn english_list<T: ToString>(list: &[T]) -> String {
-    match list.len() {
-        0 => "".to_string(),
-        1 => list[0].to_string(),
-        _ => {
-            let (last, rest) = list.split_last().unwrap();
-            format!(
-                "{} and {}",
-                intersperse(rest.iter().map(|s| s.to_string()), ", ".to_string()).collect::<String>(),
-                last.to_string()
-            )
-        }
-    }
-}
-
-impl Display for SolveResults {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl SolveResults {
+    pub fn message(&self) -> Message {
+        let cell_num = |key, pos: &Point, n: &u8| {
+            Message::new(key)
+                .with("cell", Param::Cell((*pos).into()))
+                .with("n", Param::Number(*n))
+        };
+        let cells_num = |key, list: &[Point], n: &u8| {
+            Message::new(key)
+                .with("cells", Param::Cells(list.iter().map(|&pos| pos.into()).collect()))
+                .with("n", Param::Number(*n))
+        };
         match &self.ty {
-            UpdateImpossibles => write!(f, "Remove trivially impossible numbers"),
-            Stranded => write!(f, "Remove stranded numbers"),
-            DefiniteMinMax => write!(f, "Remove unreachable numbers from compartments"),
-            Singles => write!(f, "Find hidden singles"),
-            RequiredRange => {
-                write!(f, "Remove numbers from other compartments if they are required in others")
-            }
-            Sets(n) => write!(f, "Find out sets of {} numbers", n),
-            RequiredAndForbidden => write!(f, "List required numbers and blocked numbers"),
-            RowColBrute => {
-                write!(f, "Think very hard about possible combinations in rows and columns")
+            UpdateImpossibles => Message::new("update_impossibles"),
+            Stranded => Message::new("stranded"),
+            DefiniteMinMax => Message::new("definite_min_max"),
+            Singles => Message::new("singles"),
+            RequiredRange => Message::new("required_range"),
+            Sets(n) => Message::new("sets").with("n", Param::Count(*n)),
+            RequiredAndForbidden => Message::new("required_and_forbidden"),
+            HiddenSets(list, set) => Message::new("hidden_sets")
+                .with("cells", Param::Cells(list.iter().map(|&pos| pos.into()).collect()))
+                .with("numbers", Param::Numbers(set.into_iter().collect())),
+            RowColBrute => Message::new("row_col_brute"),
+            Setti(set) => Message::new("setti").with("numbers", Param::Numbers(set.into_iter().collect())),
+            YWing(pos, n) => cell_num("y_wing", pos, n),
+            Fish(2) => Message::new("x_wing"),
+            Fish(3) => Message::new("swordfish"),
+            Fish(n) => Message::new("fish").with("n", Param::Count(*n)),
+            XyzWing(pos, removed) => Message::new("xyz_wing")
+                .with("cell", Param::Cell((*pos).into()))
+                .with("count", Param::Count(removed.len())),
+            XChain(n, list) => cells_num("x_chain", list, n),
+            FinnedFish(size, sashimi, n, list) => {
+                cells_num(if *sashimi { "sashimi_fish" } else { "finned_fish" }, list, n)
+                    .with("size", Param::Count(*size))
             }
-            Setti(set) => write!(f, "Calculate settis on {}", english_list(&set.into_iter().collect::<Vec<_>>())),
-            YWing((x, y), n) => write!(f, "Y-Wing causes ({}, {}) to not be {}", x + 1, y + 1, n),
-            Fish(2) => write!(f, "Calculate a X-wing"),
-            Fish(3) => write!(f, "Calculate a Swordfish"),
-            Fish(n) => write!(f, "Calculate a {}-fish", n),
-            Medusa => write!(f, "Calculate a 3D Medusa"),
-            UniqueRequirement(UrResult::SingleUnique((x, y), n)) => {
-                write!(f, "({}, {}) must be {}, as other solutions would be ambiguous", x + 1, y + 1, n,)
-            }
-            UniqueRequirement(UrResult::IntraCompartmentUnique((x, y), n)) => {
-                write!(f, "({}, {}) cannot be {}, as it would cause ambiguous solutions", x + 1, y + 1, n,)
+            XyChain(list, removed) => Message::new("xy_chain")
+                .with("cells", Param::Cells(list.iter().map(|&pos| pos.into()).collect()))
+                .with("count", Param::Count(removed.len())),
+            Medusa => Message::new("medusa"),
+            Aic(chain, removed) => Message::new("aic")
+                .with("cells", Param::Cells(chain.iter().map(|&(pos, _)| pos.into()).collect()))
+                .with("count", Param::Count(removed.len())),
+            UniqueRequirement(UrResult::SingleUnique(pos, n)) => cell_num("ur_single_unique", pos, n),
+            UniqueRequirement(UrResult::IntraCompartmentUnique(pos, n)) => {
+                cell_num("ur_intra_compartment_unique", pos, n)
             }
             UniqueRequirement(UrResult::ClosedSetCompartment(list, n)) => {
-                write!(
-                    f,
-                    "The cells {:?} must contain {} or the puzzle becomes ambiguous",
-                    list.iter().map(|(x, y)| (x + 1, y + 1)).collect::<Vec<_>>(),
-                    n,
-                )
+                cells_num("ur_closed_set_compartment", list, n)
             }
-            UniqueRequirement(UrResult::SingleCellWouldBecomeFree((x, y), n)) => {
-                write!(f, "({}, {}) cannot be {}, as it would cause ambiguous solutions", x + 1, y + 1, n,)
+            UniqueRequirement(UrResult::SingleCellWouldBecomeFree(pos, n)) => {
+                cell_num("ur_single_cell_would_become_free", pos, n)
             }
             UniqueRequirement(UrResult::UrSetti(list, vertical, n)) => {
-                write!(
-                    f,
-                    "The {} containing points {:?} must contain {}, or the puzzle becomes ambiguous",
-                    if *vertical { "columns" } else { "rows" },
-                    list.iter().map(|(x, y)| (x + 1, y + 1)).collect::<Vec<_>>(),
-                    n,
-                )
+                cells_num("ur_setti", list, n).with("lines", Param::Lines(*vertical))
+            }
+            UniqueRequirement(UrResult::SolutionCausesClosedSets(pos, n)) => {
+                cell_num("ur_solution_causes_closed_sets", pos, n)
             }
-            UniqueRequirement(UrResult::SolutionCausesClosedSets((x, y), n)) => {
-                write!(
-                    f,
-                    "Setting ({}, {}) to {} creates closed sets, causing puzzle to become ambiguous",
-                    x + 1,
-                    y + 1,
-                    n,
-                )
+            PatternOverlay(n, removed, placed) if placed.is_empty() => cells_num("pattern_overlay", removed, n),
+            PatternOverlay(n, _, placed) => cells_num("pattern_overlay_place", placed, n),
+            ForcingChain(branches, removed, placed) if branches.iter().all(|(pos, _)| *pos == branches[0].0) => {
+                Message::new("cell_forcing_chain")
+                    .with("cell", Param::Cell(branches[0].0.into()))
+                    .with("count", Param::Count(removed.len()))
+                    .with("placed", Param::Count(placed.len()))
             }
-            StartGuess((x, y), n) => write!(f, "Start guess with ({}, {}) = {}", x + 1, y + 1, n),
-            GuessStep((x, y), n, steps, _) => write!(
-                f,
-                "({}, {}) cannot be {}, as it causes a conflict in {} steps",
-                x + 1,
-                y + 1,
-                n,
-                steps.len(),
-            ),
-            EndGuess(end) => write!(f, "{}", end),
-            PuzzleSolved => write!(f, "Puzzle solved"),
-            EnumerateSolutions => write!(f, "Enumerate all possible solutions"),
+            ForcingChain(branches, removed, placed) => Message::new("unit_forcing_chain")
+                .with("cells", Param::Cells(branches.iter().map(|&(pos, _)| pos.into()).collect()))
+                .with("n", Param::Number(branches[0].1))
+                .with("count", Param::Count(removed.len()))
+                .with("placed", Param::Count(placed.len())),
+            StartGuess(pos, n) => cell_num("start_guess", pos, n),
+            GuessStep(pos, n, steps, _) => cell_num("guess_step", pos, n).with("count", Param::Count(steps.len())),
+            EndGuess(end) => end.message(),
+            PuzzleSolved => Message::new("puzzle_solved"),
+            EnumerateSolutions => Message::new("enumerate_solutions"),
         }
     }
 }
 
+impl Display for SolveResults {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ValidationError {
     EmptyCell {
@@ -198,8 +226,11 @@ pub enum ValidationError {
         index: usize,
         number: u8,
     },
+    /* Each of the solutions lists the numbers it puts in the cells, in the same order. It can be empty when the
+     * ambiguity was spotted without solving the puzzle. */
     Ambiguous {
         cells: Vec<Point>,
+        solutions: Vec<Vec<u8>>,
     },
     NoSolutions,
     OutOfStrats,
@@ -225,45 +256,69 @@ pub fn into_ty(res: Result<SolveResults, ValidationResult>) -> Result<SolveType,
     }
 }
 
-impl Display for ValidationResult {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl ValidationResult {
+    pub fn message(&self) -> Message {
+        let line = |key, vertical: &bool, index: &usize, number: &u8| {
+            Message::new(key)
+                .with("n", Param::Number(*number))
+                .with("line", Param::Line(*vertical))
+                .with("index", Param::Index(*index))
+        };
         match &self.ty {
-            EmptyCell { pos: (x, y) } => write!(
-                f,
-                "Cell scan: Cell ({}, {}) ran out of possible options",
-                x + 1,
-                y + 1
-            ),
-            Conflict { pos1, pos2, val } => write!(
-                f,
-                "Cells ({}, {}) and ({}, {}) both contain {}",
-                pos1.0 + 1,
-                pos1.1 + 1,
-                pos2.0 + 1,
-                pos2.1 + 1,
-                val
-            ),
-            Sequence { vertical, missing, range: (min, max), top_left: (x, y) } =>
-                write!(f, "Compartment scan: {} compartment starting from ({}, {}) contains numbers {} and {}, but it doesn't contain {} making it non-contiguous",
-                       if *vertical { "Vertical" } else { "Horizontal" }, x+1, y+1, min, max, missing),
-            SequenceTooLarge { vertical, max_ranges: ((min_a, max_a), (min_b, max_b)), top_left: (x, y), contains: (a, b) } =>
-                write!(f, "Compartment scan: {} compartment starting from ({}, {}) contains numbers {} and {}, but it's too small for them as it can either contain {} to {} or {} to {}",
-                       if *vertical { "Vertical" } else { "Horizontal" }, x+1, y+1, a, b, min_a, max_a, min_b, max_b),
-            RequirementBlockerConflict { vertical, index, number } =>
-                write!(f, "The number {} is included both in required and blocked numbers for {} {}",
-                       number, if *vertical {"column"} else {"row"}, index + 1
-                ),
-            RequiredNumberMissing { vertical, index, number } =>
-                write!(f, "The number {} is required in {} {} but not present in any container",
-                       number, if *vertical {"column"} else {"row"}, index + 1
-                ),
-            BlockedNumberPresent { vertical, index, number } =>
-                write!(f, "The number {} is forbidden in {} {} but is a solution or a requirement",
-                       number, if *vertical {"column"} else {"row"}, index + 1
-                ),
-            Ambiguous { .. } => write!(f, "Grid is ambiguous, and cannot be solved"),
-            NoSolutions => write!(f, "Exhaustive search proves grid has no solutions"),
-            OutOfStrats => write!(f, "Ran out of strategies!"),
+            EmptyCell { pos } => Message::new("empty_cell").with("cell", Param::Cell((*pos).into())),
+            Conflict { pos1, pos2, val } => Message::new("conflict")
+                .with("cell1", Param::Cell((*pos1).into()))
+                .with("cell2", Param::Cell((*pos2).into()))
+                .with("n", Param::Number(*val)),
+            Sequence {
+                vertical,
+                missing,
+                range: (min, max),
+                top_left,
+            } => Message::new("sequence")
+                .with("direction", Param::Direction(*vertical))
+                .with("cell", Param::Cell((*top_left).into()))
+                .with("min", Param::Number(*min))
+                .with("max", Param::Number(*max))
+                .with("missing", Param::Number(*missing)),
+            SequenceTooLarge {
+                vertical,
+                max_ranges: ((min_a, max_a), (min_b, max_b)),
+                top_left,
+                contains: (a, b),
+            } => Message::new("sequence_too_large")
+                .with("direction", Param::Direction(*vertical))
+                .with("cell", Param::Cell((*top_left).into()))
+                .with("a", Param::Number(*a))
+                .with("b", Param::Number(*b))
+                .with("min_a", Param::Number(*min_a))
+                .with("max_a", Param::Number(*max_a))
+                .with("min_b", Param::Number(*min_b))
+                .with("max_b", Param::Number(*max_b)),
+            RequirementBlockerConflict {
+                vertical,
+                index,
+                number,
+            } => line("requirement_blocker_conflict", vertical, index, number),
+            RequiredNumberMissing {
+                vertical,
+                index,
+                number,
+            } => line("required_number_missing", vertical, index, number),
+            BlockedNumberPresent {
+                vertical,
+                index,
+                number,
+            } => line("blocked_number_present", vertical, index, number),
+            Ambiguous { .. } => Message::new("ambiguous"),
+            NoSolutions => Message::new("no_solutions"),
+            OutOfStrats => Message::new("out_of_strats"),
         }
     }
 }
+
+impl Display for ValidationResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
@@ -32,19 +32,32 @@ pub fn run_strat(grid: &mut Grid, strats: &StrategyList) -> Result<SolveResults,
 
         /* advanced strats */
         run_strat!(strats, Strategy::RequiredAndForbidden, strats::update_required_and_forbidden(grid));
+        run_strat!(strats, Strategy::HiddenSets, strats::hidden_sets(grid));
         run_strat!(strats, Strategy::Setti, strats::setti(grid));
         run_strat!(strats, Strategy::RowColBrute, strats::row_col_brute(grid));
         run_strat!(strats, Strategy::YWing, strats::y_wing(grid));
+        run_strat!(strats, Strategy::XyzWing, strats::xyz_wing(grid));
         run_strat!(strats, Strategy::Fish, strats::fish(grid));
+        run_strat!(strats, Strategy::XChain, strats::x_chain(grid));
+        run_strat!(strats, Strategy::XyChain, strats::xy_chain(grid));
 
         run_strat!(strats, Strategy::Medusa, strats::medusa(grid));
+        run_strat!(strats, Strategy::Aic, strats::aic(grid));
+        run_strat!(strats, Strategy::FinnedFish, strats::finned_fish(grid));
 
         run_strat!(strats, Strategy::UniqueRequirement, strats::unique_requirement(grid));
+        run_strat!(strats, Strategy::PatternOverlay, strats::pattern_overlay(grid));
         run_strat!(strats, Strategy::UniqueRequirementGuess, strats::unique_requirement_guess(grid));
+        run_strat!(strats, Strategy::ForcingChain, strats::forcing_chain(grid));
 
-        run_strat!(strats, Strategy::Guess, strats::guess(grid));
+        run_strat!(strats, Strategy::Guess, strats::guess(grid, strats));
 
-        run_strat!(strats, Strategy::EnumerateSolutions, strats::enumerate_solutions(grid));
+        /* without uniqueness deductions, search for solutions however many cells are left */
+        if strats.assumes_uniqueness() {
+            run_strat!(strats, Strategy::EnumerateSolutions, strats::enumerate_solutions(grid));
+        } else {
+            run_strat!(strats, Strategy::EnumerateSolutions, strats::search_solutions(grid));
+        }
 
         Err(OutOfStrats.into())
     })();
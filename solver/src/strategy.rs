@@ -11,13 +11,21 @@ pub enum Strategy {
     RequiredRange,
     Sets,
     RequiredAndForbidden,
+    HiddenSets,
     RowColBrute,
     Setti,
     YWing,
+    XyzWing,
     Fish,
+    XChain,
+    XyChain,
     Medusa,
+    Aic,
+    FinnedFish,
     UniqueRequirement,
+    PatternOverlay,
     UniqueRequirementGuess,
+    ForcingChain,
     Guess,
     EnumerateSolutions,
 }
@@ -32,13 +40,21 @@ impl Strategy {
             Strategy::RequiredRange => 4,
             Strategy::Sets => 4,
             Strategy::RequiredAndForbidden => 5,
+            Strategy::HiddenSets => 5,
             Strategy::RowColBrute => 5,
             Strategy::Setti => 5,
             Strategy::YWing => 5,
             Strategy::Fish => 5,
+            Strategy::XyzWing => 5,
+            Strategy::XChain => 6,
+            Strategy::XyChain => 6,
             Strategy::Medusa => 6,
+            Strategy::Aic => 6,
+            Strategy::FinnedFish => 6,
             Strategy::UniqueRequirement => 6,
+            Strategy::PatternOverlay => 7,
             Strategy::UniqueRequirementGuess => 7,
+            Strategy::ForcingChain => 6,
             Strategy::Guess => 7,
             Strategy::EnumerateSolutions => 7,
         }
@@ -53,31 +69,44 @@ const ALL_STRATEGIES: &[Strategy] = &[
     Strategy::RequiredRange,
     Strategy::Sets,
     Strategy::RequiredAndForbidden,
+    Strategy::HiddenSets,
     Strategy::RowColBrute,
     Strategy::Setti,
     Strategy::YWing,
+    Strategy::XyzWing,
     Strategy::Fish,
+    Strategy::XChain,
+    Strategy::XyChain,
     Strategy::Medusa,
+    Strategy::Aic,
+    Strategy::FinnedFish,
     Strategy::UniqueRequirement,
+    Strategy::PatternOverlay,
     Strategy::UniqueRequirementGuess,
+    Strategy::ForcingChain,
     Strategy::Guess,
     Strategy::EnumerateSolutions,
 ];
 
+/* Strategies which rely on the puzzle having a unique solution */
+const UNIQUENESS_STRATEGIES: &[Strategy] = &[Strategy::UniqueRequirement, Strategy::UniqueRequirementGuess];
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct StrategyList {
     strats: HashMap<Strategy, bool>,
+    unique: bool,
 }
 
 impl StrategyList {
     pub fn new(strats: &[Strategy]) -> StrategyList {
         StrategyList {
             strats: strats.iter().map(|&strat| (strat, true)).collect(),
+            unique: true,
         }
     }
 
     pub fn all() -> StrategyList {
-        StrategyList::new(&ALL_STRATEGIES)
+        StrategyList::new(ALL_STRATEGIES)
     }
 
     pub fn for_difficulty(difficulty: usize) -> StrategyList {
@@ -108,6 +137,7 @@ impl StrategyList {
     pub fn no_guesses() -> StrategyList {
         StrategyList::all().except(&[
             Strategy::UniqueRequirementGuess,
+            Strategy::ForcingChain,
             Strategy::Guess,
             Strategy::EnumerateSolutions,
         ])
@@ -118,11 +148,27 @@ impl StrategyList {
         for &strat in without_strats {
             strats.insert(strat, false);
         }
-        StrategyList { strats }
+        StrategyList {
+            strats,
+            unique: self.unique,
+        }
+    }
+
+    /* Forbid deductions which assume the puzzle to have a unique solution. When the other strategies run out,
+     * the solver searches for solutions instead, so ambiguous puzzles are reported with two of them. */
+    pub fn without_uniqueness(&self) -> StrategyList {
+        StrategyList {
+            unique: false,
+            ..self.except(UNIQUENESS_STRATEGIES)
+        }
+    }
+
+    pub fn assumes_uniqueness(&self) -> bool {
+        self.unique
     }
 
     pub fn has(&self, strat: Strategy) -> bool {
-        *self.strats.get(&strat).unwrap_or(&false)
+        (self.unique || !UNIQUENESS_STRATEGIES.contains(&strat)) && *self.strats.get(&strat).unwrap_or(&false)
     }
 }
 
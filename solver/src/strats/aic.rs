@@ -0,0 +1,187 @@
+use crate::grid::{Compartment, Grid};
+use crate::solve_result::SolveType::Aic;
+use crate::solve_result::{SolveMetadata, SolveResults};
+use crate::strategy::StrategyReturn;
+use crate::strats::medusa::gather_pairs;
+use crate::strats::xy_chain::{eliminations, Peers};
+use crate::strats::{get_compartment_range, num_count_in_containers, Candidate};
+use itertools::Itertools;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+
+type Links = BTreeMap<Candidate, BTreeSet<Candidate>>;
+
+fn add_link(links: &mut Links, a: Candidate, b: Candidate) {
+    links.entry(a).or_default().insert(b);
+    links.entry(b).or_default().insert(a);
+}
+
+/* A compartment of length n which can only span n + 1 numbers holds at least one of the two ends of the range */
+fn compartment_link(grid: &Grid, compartment: &Compartment) -> Option<(Candidate, Candidate)> {
+    let index = if compartment.vertical {
+        compartment.sample_pos().0
+    } else {
+        compartment.sample_pos().1
+    };
+    let pos = (index, index);
+    let mut range = get_compartment_range(grid.x, compartment, None)?;
+    /* required numbers which no other compartment in the line can hold narrow the range */
+    for n in grid.requirements(compartment.vertical, pos) {
+        if compartment.contains(n) && num_count_in_containers(grid, compartment, n) == 1 {
+            let (min, max) = get_compartment_range(grid.x, compartment, Some(n))?;
+            range = (range.0.max(min), range.1.min(max));
+        }
+    }
+    let (lo, hi) = range;
+    if hi < lo || (hi - lo) as usize != compartment.cells.len() {
+        return None;
+    }
+    let forbidden = grid.forbidden(compartment.vertical, pos);
+    if forbidden.contains(lo) || forbidden.contains(hi) {
+        return None;
+    }
+
+    let only_cell = |n: u8| {
+        let cells = compartment
+            .to_unresolved()
+            .into_iter()
+            .filter(|(_, set)| set.contains(n))
+            .map(|(pos, _)| pos)
+            .collect::<Vec<_>>();
+        match cells[..] {
+            [pos]
+                if !compartment
+                    .cells
+                    .iter()
+                    .any(|(_, cell)| cell.to_determinate() == Some(n)) =>
+            {
+                Some(pos)
+            }
+            _ => None,
+        }
+    };
+    Some(((only_cell(lo)?, lo), (only_cell(hi)?, hi)))
+}
+
+/* Pairs of candidates of which at least one is true */
+fn strong_links(grid: &Grid) -> Links {
+    let mut links = Links::new();
+    for (pos, set) in grid.iter_by_indeterminates() {
+        if set.len() == 2 {
+            let [a, b] = set.into_iter().collect::<Vec<_>>()[..] else {
+                unreachable!()
+            };
+            add_link(&mut links, (pos, a), (pos, b));
+        }
+    }
+    for ((pos, n), others) in gather_pairs(grid).into_iter().sorted() {
+        for other in others {
+            add_link(&mut links, (pos, n), (other, n));
+        }
+    }
+    for compartment in grid.iter_by_compartments() {
+        if let Some((a, b)) = compartment_link(grid, &compartment) {
+            add_link(&mut links, a, b);
+        }
+    }
+    links
+}
+
+type Node = (Candidate, bool);
+
+/* Alternating inference chains: starting from a candidate assumed false, strong links make the next candidate
+ * true and conflicts make the one after that false. Either the first or the last candidate is true, so anything
+ * conflicting with both is removed. */
+pub fn aic(grid: &mut Grid) -> StrategyReturn {
+    let peers = Peers::new(grid);
+    let strong = strong_links(grid);
+    let weak: Links = strong
+        .keys()
+        .map(|&a| (a, strong.keys().copied().filter(|&b| peers.conflicts(a, b)).collect()))
+        .collect();
+
+    for &start in strong.keys() {
+        let first: Node = (start, false);
+        let mut parents: HashMap<Node, Node> = HashMap::new();
+        let mut queue = VecDeque::from([first]);
+        while let Some(node) = queue.pop_front() {
+            let (candidate, on) = node;
+            let next = if on { &weak[&candidate] } else { &strong[&candidate] };
+            for &other in next {
+                let next_node = (other, !on);
+                if next_node == first || parents.contains_key(&next_node) {
+                    continue;
+                }
+                parents.insert(next_node, node);
+                queue.push_back(next_node);
+                if on {
+                    continue;
+                }
+
+                let removed = eliminations(grid, &peers, &[start, other]);
+                if removed.is_empty() {
+                    continue;
+                }
+                let mut chain = vec![next_node];
+                while let Some(&parent) = parents.get(chain.last().unwrap()) {
+                    chain.push(parent);
+                }
+                chain.reverse();
+                for &(pos, n) in &removed {
+                    grid.set_impossible(pos, n)?;
+                }
+                let colors = [false, true]
+                    .into_iter()
+                    .map(|on| chain.iter().filter(|node| node.1 == on).map(|node| node.0).collect())
+                    .collect();
+                let meta = SolveMetadata {
+                    colors,
+                    ..Default::default()
+                };
+                let ty = Aic(chain.into_iter().map(|node| node.0).collect(), removed);
+                return Ok(Some(SolveResults { ty, meta }));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solve_result::ValidationError::OutOfStrats;
+    use crate::solver::solve_basic;
+    use crate::utils::*;
+
+    #[test]
+    fn test_aic() {
+        let mut grid = g("
+....#..
+.......
+.......
+...#...
+.......
+.......
+.......
+");
+        grid.cells[3][0] = det([1, 2, 3]);
+        grid.cells[3][1] = det([2, 3]);
+        grid.cells[3][2] = det([2, 3, 4]);
+        grid.cells[0][2] = det([4, 6]);
+        assert_eq!(solve_basic(&mut grid), Err(OutOfStrats));
+        assert_eq!(grid.cells[0][0], det([1, 2, 3, 4, 5, 6, 7]));
+
+        /* the compartment in row 4 spans 1 to 4, so it has 1 in (0, 3) or 4 in (2, 3) */
+        assert_eq!(
+            aic(&mut grid),
+            Ok(Some(SolveResults {
+                ty: Aic(vec![((0, 3), 1), ((2, 3), 4), ((2, 0), 4), ((2, 0), 6)], vec![((0, 0), 1)]),
+                meta: SolveMetadata {
+                    colors: vec![vec![((0, 3), 1), ((2, 0), 4)], vec![((2, 3), 4), ((2, 0), 6)]],
+                    ..Default::default()
+                }
+            }))
+        );
+        assert_eq!(grid.cells[0][0], det([2, 3, 4, 5, 6, 7]));
+    }
+}
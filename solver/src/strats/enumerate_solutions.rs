@@ -2,6 +2,8 @@ use crate::bitset::BitSet;
 use crate::grid::Cell::Solution;
 use crate::grid::{Grid, Point};
 use crate::solve_result::{SolveMetadata, SolveResults, SolveType, ValidationError, ValidationResult};
+use crate::solver::run_strat;
+use crate::strategy::StrategyList;
 use crate::validator::validate;
 
 fn iter(grid: &Grid, indeterminates: &[(Point, BitSet)]) -> Vec<Grid> {
@@ -22,6 +24,38 @@ fn iter(grid: &Grid, indeterminates: &[(Point, BitSet)]) -> Vec<Grid> {
     }
 }
 
+/* Depth-first search for up to `limit` solutions, running the basic strategies before each branch */
+fn search(grid: &Grid, limit: usize, solutions: &mut Vec<Grid>) {
+    let strats = StrategyList::basic();
+    let mut grid = grid.clone();
+    loop {
+        match run_strat(&mut grid, &strats) {
+            Ok(res) if res.ty == SolveType::PuzzleSolved => {
+                solutions.push(grid);
+                return;
+            }
+            Ok(_) => {}
+            Err(e) if e.ty == ValidationError::OutOfStrats => break,
+            Err(_) => return,
+        }
+    }
+    let Some((pos, set)) = grid
+        .iter_by_indeterminates()
+        .into_iter()
+        .min_by_key(|(_, set)| set.len())
+    else {
+        return;
+    };
+    for num in set {
+        if solutions.len() >= limit {
+            return;
+        }
+        let mut inner = grid.clone();
+        inner.set_cell(pos, Solution(num));
+        search(&inner, limit, solutions);
+    }
+}
+
 const MAX_INDETERMINATES: usize = 8;
 pub fn enumerate_solutions(grid: &mut Grid) -> Result<Option<SolveResults>, ValidationResult> {
     let indeterminates = grid.iter_by_indeterminates();
@@ -30,36 +64,63 @@ pub fn enumerate_solutions(grid: &mut Grid) -> Result<Option<SolveResults>, Vali
     }
 
     let solutions = iter(grid, &indeterminates);
+    report(grid, &indeterminates, solutions)
+}
+
+/* Like enumerate_solutions, but with no limit on the unresolved cells. Stops after two solutions, which are
+ * enough to show the puzzle ambiguous. */
+pub fn search_solutions(grid: &mut Grid) -> Result<Option<SolveResults>, ValidationResult> {
+    let indeterminates = grid.iter_by_indeterminates();
+    let mut solutions = Vec::new();
+    search(grid, 2, &mut solutions);
+    report(grid, &indeterminates, solutions)
+}
+
+fn report(
+    grid: &mut Grid,
+    indeterminates: &[(Point, BitSet)],
+    solutions: Vec<Grid>,
+) -> Result<Option<SolveResults>, ValidationResult> {
     if solutions.is_empty() {
-        /* Should be unreachable */
         Err(ValidationResult {
             ty: ValidationError::NoSolutions,
-            meta: SolveMetadata { colors: vec![] },
+            meta: SolveMetadata {
+                colors: vec![],
+                ..Default::default()
+            },
         })
     } else if solutions.len() == 1 {
-        /* Should be unreachable */
         for (pos, cell) in solutions[0].iter_by_cells() {
             grid.set_cell(pos, cell);
         }
         Ok(Some(SolveResults {
             ty: SolveType::EnumerateSolutions,
-            meta: SolveMetadata { colors: vec![] },
+            meta: SolveMetadata {
+                colors: vec![],
+                ..Default::default()
+            },
         }))
     } else {
-        let colors = solutions
+        let cells = indeterminates.iter().map(|(pos, _)| *pos).collect::<Vec<_>>();
+        let solutions = solutions
             .into_iter()
             .map(|grid| {
-                indeterminates
+                cells
                     .iter()
-                    .map(|&(pos, _)| (pos, grid.get_cell(pos).to_determinate().unwrap()))
-                    .collect()
+                    .map(|&pos| grid.get_cell(pos).to_determinate().unwrap())
+                    .collect::<Vec<_>>()
             })
+            .collect::<Vec<_>>();
+        let colors = solutions
+            .iter()
+            .map(|solution| cells.iter().copied().zip(solution.iter().copied()).collect())
             .collect();
         Err(ValidationResult {
-            ty: ValidationError::Ambiguous {
-                cells: indeterminates.iter().map(|(pos, _)| *pos).collect(),
+            ty: ValidationError::Ambiguous { cells, solutions },
+            meta: SolveMetadata {
+                colors,
+                ..Default::default()
             },
-            meta: SolveMetadata { colors },
         })
     }
 }
@@ -80,7 +141,10 @@ mod tests {
             enumerate_solutions(&mut grid),
             Err(ValidationResult {
                 ty: ValidationError::NoSolutions,
-                meta: SolveMetadata { colors: vec![] }
+                meta: SolveMetadata {
+                    colors: vec![],
+                    ..Default::default()
+                }
             })
         );
     }
@@ -96,7 +160,10 @@ mod tests {
             enumerate_solutions(&mut grid),
             Ok(Some(SolveResults {
                 ty: SolveType::EnumerateSolutions,
-                meta: SolveMetadata { colors: vec![] }
+                meta: SolveMetadata {
+                    colors: vec![],
+                    ..Default::default()
+                }
             }))
         );
         assert_eq!(grid.cells[0][1], Solution(2));
@@ -113,13 +180,15 @@ mod tests {
             enumerate_solutions(&mut grid),
             Err(ValidationResult {
                 ty: ValidationError::Ambiguous {
-                    cells: vec![(0, 0), (1, 0), (0, 1), (1, 1)]
+                    cells: vec![(0, 0), (1, 0), (0, 1), (1, 1)],
+                    solutions: vec![vec![1, 2, 2, 1], vec![2, 1, 1, 2]]
                 },
                 meta: SolveMetadata {
                     colors: vec![
                         vec![((0, 0), 1), ((1, 0), 2), ((0, 1), 2), ((1, 1), 1)],
                         vec![((0, 0), 2), ((1, 0), 1), ((0, 1), 1), ((1, 1), 2)]
-                    ]
+                    ],
+                    ..Default::default()
                 }
             })
         );
@@ -137,4 +206,74 @@ mod tests {
 
         assert_eq!(enumerate_solutions(&mut grid), Ok(None));
     }
+
+    #[test]
+    fn test_search_over_limit() {
+        let mut grid = g("
+.....
+.....
+.....
+.....
+.....
+");
+
+        let Err(ValidationResult {
+            ty: ValidationError::Ambiguous { cells, solutions },
+            meta,
+        }) = search_solutions(&mut grid)
+        else {
+            panic!("expected the grid to be ambiguous")
+        };
+        assert_eq!(cells.len(), 25);
+        assert_eq!(solutions.len(), 2);
+        assert_ne!(solutions[0], solutions[1]);
+        assert_eq!(meta.colors.len(), 2);
+        for numbers in solutions {
+            let mut solution = grid.clone();
+            for (&pos, n) in cells.iter().zip(numbers) {
+                solution.set_cell(pos, Solution(n));
+            }
+            assert!(solution.is_solved());
+            assert_eq!(validate(&solution), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_without_uniqueness() {
+        let puzzle = g("
+#4..
+..#.
+.#..
+..4#
+");
+        let solve = |strats: &StrategyList| {
+            let mut grid = puzzle.clone();
+            loop {
+                match run_strat(&mut grid, strats) {
+                    Ok(res) if res.ty == SolveType::PuzzleSolved => return Ok(grid),
+                    Ok(_) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        };
+        /* the uniqueness deductions settle on one of the solutions */
+        assert!(solve(&StrategyList::all()).is_ok());
+
+        assert_eq!(
+            solve(&StrategyList::all().without_uniqueness()),
+            Err(ValidationResult {
+                ty: ValidationError::Ambiguous {
+                    cells: vec![(0, 1), (3, 1), (0, 2), (3, 2)],
+                    solutions: vec![vec![2, 1, 1, 2], vec![2, 1, 4, 2]]
+                },
+                meta: SolveMetadata {
+                    colors: vec![
+                        vec![((0, 1), 2), ((3, 1), 1), ((0, 2), 1), ((3, 2), 2)],
+                        vec![((0, 1), 2), ((3, 1), 1), ((0, 2), 4), ((3, 2), 2)]
+                    ],
+                    ..Default::default()
+                }
+            })
+        );
+    }
 }
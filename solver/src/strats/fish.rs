@@ -1,8 +1,12 @@
 use crate::grid::{CellPair, Grid, Point};
-use crate::solve_result::SolveType::Fish;
+use crate::solve_result::SolveType::{FinnedFish, Fish};
 use crate::solve_result::{SolveMetadata, SolveResults, ValidationResult};
+use crate::strategy::StrategyReturn;
+use crate::strats::basic_consequences;
+use crate::strats::Candidate;
 use itertools::Itertools;
 use rustc_hash::FxHashSet;
+use std::collections::{BTreeSet, HashMap};
 
 fn get_cells_with_indeterminate_num(line: &[CellPair], num: u8) -> Vec<Point> {
     let cells: Vec<Point> = line
@@ -99,7 +103,10 @@ pub fn fish(grid: &mut Grid) -> Result<Option<SolveResults>, ValidationResult> {
         if changes {
             return Ok(Some(SolveResults {
                 ty: Fish(fish_count),
-                meta: SolveMetadata { colors },
+                meta: SolveMetadata {
+                    colors,
+                    ..Default::default()
+                },
             }));
         }
     }
@@ -107,12 +114,112 @@ pub fn fish(grid: &mut Grid) -> Result<Option<SolveResults>, ValidationResult> {
     Ok(None)
 }
 
+/* A fish whose base lines also hold the number in fins, all of which sit on one line crossing the base lines
+ * outside the cover lines. Either a fin holds the number or the rest is a plain fish, so a cell of the cover
+ * lines outside the base lines can be cleared if placing the number in any of the fins clears it as well.
+ * A sashimi fish has only one cell of some base line in the body. */
+pub fn finned_fish(grid: &mut Grid) -> StrategyReturn {
+    let mut consequences: HashMap<Candidate, Option<Grid>> = HashMap::new();
+
+    for fish_count in 2..=4.min(grid.x.saturating_sub(1)) {
+        for vertical in [false, true] {
+            let (lines, reqs) = if vertical {
+                (grid.iter_by_cols(), &grid.col_requirements)
+            } else {
+                (grid.iter_by_rows(), &grid.row_requirements)
+            };
+            let line_of = |(x, y): Point| if vertical { x } else { y };
+            let cross_of = |(x, y): Point| if vertical { y } else { x };
+
+            for num in 1..=grid.x as u8 {
+                let bases: Vec<(usize, Vec<Point>)> = lines
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, _)| reqs[*idx].contains(num))
+                    .map(|(idx, line)| (idx, get_cells_with_indeterminate_num(line, num)))
+                    .filter(|(_, cells)| !cells.is_empty())
+                    .collect();
+                let with_num = grid
+                    .iter_by_indeterminates()
+                    .into_iter()
+                    .filter(|(_, set)| set.contains(num))
+                    .map(|(pos, _)| pos)
+                    .collect::<Vec<_>>();
+
+                for base in bases.iter().combinations(fish_count) {
+                    let cells: Vec<Point> = base.iter().flat_map(|(_, cells)| cells.iter().copied()).collect();
+                    let crossing: BTreeSet<usize> = cells.iter().map(|&pos| cross_of(pos)).collect();
+                    if crossing.len() != fish_count + 1 {
+                        continue;
+                    }
+
+                    for cover in crossing.into_iter().combinations(fish_count) {
+                        let (body, fins): (Vec<Point>, Vec<Point>) =
+                            cells.iter().partition(|&&pos| cover.contains(&cross_of(pos)));
+                        let body_counts = base
+                            .iter()
+                            .map(|(idx, _)| body.iter().filter(|&&pos| line_of(pos) == *idx).count())
+                            .collect::<Vec<_>>();
+                        if body_counts.contains(&0)
+                            || cover.iter().any(|&c| !body.iter().any(|&pos| cross_of(pos) == c))
+                        {
+                            continue;
+                        }
+
+                        let removed = with_num
+                            .iter()
+                            .copied()
+                            .filter(|&pos| {
+                                cover.contains(&cross_of(pos)) && !base.iter().any(|(idx, _)| line_of(pos) == *idx)
+                            })
+                            .filter(|&target| {
+                                fins.iter().all(|&fin| {
+                                    consequences
+                                        .entry((fin, num))
+                                        .or_insert_with(|| basic_consequences(grid, &[(fin, num)]))
+                                        .as_ref()
+                                        .is_none_or(|after| !after.get_cell(target).to_possibles().contains(num))
+                                })
+                            })
+                            .collect::<Vec<_>>();
+                        if removed.is_empty() {
+                            continue;
+                        }
+
+                        for &pos in &removed {
+                            grid.set_impossible(pos, num)?;
+                        }
+                        let sashimi = body_counts.contains(&1);
+                        let meta = SolveMetadata {
+                            colors: vec![
+                                body.into_iter().map(|pos| (pos, num)).collect(),
+                                fins.into_iter().map(|pos| (pos, num)).collect(),
+                            ],
+                            lines: vec![
+                                base.iter().map(|(idx, _)| (vertical, *idx)).collect(),
+                                cover.into_iter().map(|c| (!vertical, c)).collect(),
+                            ],
+                        };
+                        return Ok(Some(SolveResults {
+                            ty: FinnedFish(fish_count, sashimi, num, removed),
+                            meta,
+                        }));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::solve_result::SolveType::{RequiredAndForbidden, Setti};
     use crate::solve_result::ValidationError::OutOfStrats;
-    use crate::solver::solve_basic;
+    use crate::solver::{run_strat, solve_basic};
+    use crate::strategy::{Strategy, StrategyList};
     use crate::strats::{setti, update_required_and_forbidden};
     use crate::utils::*;
 
@@ -142,7 +249,8 @@ mod tests {
             Ok(Some(SolveResults {
                 ty: Fish(2),
                 meta: SolveMetadata {
-                    colors: vec![vec![((2, 3), 3), ((2, 4), 3), ((3, 3), 3), ((3, 4), 3)]]
+                    colors: vec![vec![((2, 3), 3), ((2, 4), 3), ((3, 3), 3), ((3, 4), 3)]],
+                    ..Default::default()
                 }
             })),
             fish(&mut grid)
@@ -155,4 +263,37 @@ mod tests {
         assert_eq!(grid.cells[2][2], det([1, 2, 4, 5]));
         assert_eq!(grid.cells[2][3], det([1, 2, 4, 5]));
     }
+
+    #[test]
+    fn test_finned_fish() {
+        let mut grid = g("
+...#...
+#......
+.#.e...
+..6#...
+...d.#.
+......#
+...#...
+");
+        let strats = StrategyList::for_difficulty(6).except(&[Strategy::FinnedFish, Strategy::UniqueRequirement]);
+        while run_strat(&mut grid, &strats).is_ok() {}
+        assert_eq!(grid.cells[5][5], det([1, 2, 5, 6, 7]));
+
+        /* rows 2 and 3 have their 2 in columns 4 and 5 or in the fins at (6, 2) and (6, 3), and either fin
+         * clears 2 from (5, 5) as well */
+        assert_eq!(
+            finned_fish(&mut grid),
+            Ok(Some(SolveResults {
+                ty: FinnedFish(2, false, 2, vec![(5, 5)]),
+                meta: SolveMetadata {
+                    colors: vec![
+                        vec![((4, 2), 2), ((5, 2), 2), ((4, 3), 2), ((5, 3), 2)],
+                        vec![((6, 2), 2), ((6, 3), 2)]
+                    ],
+                    lines: vec![vec![(false, 2), (false, 3)], vec![(true, 4), (true, 5)]]
+                }
+            }))
+        );
+        assert_eq!(grid.cells[5][5], det([1, 5, 6, 7]));
+    }
 }
@@ -0,0 +1,176 @@
+use crate::grid::Cell::*;
+use crate::grid::Grid;
+use crate::solve_result::SolveType::{ForcingChain, PuzzleSolved};
+use crate::solve_result::{SolveMetadata, SolveResults, ValidationError};
+use crate::solver::run_strat;
+use crate::strategy::{StrategyList, StrategyReturn};
+use crate::strats::Candidate;
+
+const MAX_BRANCHES: usize = 3;
+const MAX_DEPTH: usize = 20;
+
+/* The grid after assuming the candidate and running the basic strategies for a while, or None if the assumption
+ * breaks the puzzle */
+fn follow(grid: &Grid, (pos, n): Candidate) -> Option<Grid> {
+    let strats = StrategyList::basic();
+    let mut grid = grid.clone();
+    grid.set_cell(pos, Solution(n));
+    for _ in 0..MAX_DEPTH {
+        match run_strat(&mut grid, &strats) {
+            Ok(res) if res.ty == PuzzleSolved => break,
+            Ok(_) => {}
+            Err(e) if e.ty == ValidationError::OutOfStrats => break,
+            Err(_) => return None,
+        }
+    }
+    Some(grid)
+}
+
+/* Candidates which none of the branches leaves in place, and numbers which all of them place. One of the branches
+ * is true, so these can be removed and placed. Cells which get placed are left out of the removals. */
+fn common_conclusions(grid: &Grid, branches: &[Candidate]) -> (Vec<Candidate>, Vec<Candidate>) {
+    let outcomes = branches
+        .iter()
+        .filter_map(|&candidate| follow(grid, candidate))
+        .collect::<Vec<_>>();
+    if outcomes.is_empty() {
+        return (vec![], vec![]);
+    }
+    let unresolved = grid.iter_by_indeterminates();
+    let placed = unresolved
+        .iter()
+        .filter_map(|&(pos, _)| match outcomes[0].get_cell(pos) {
+            Solution(n) if outcomes.iter().all(|outcome| *outcome.get_cell(pos) == Solution(*n)) => Some((pos, *n)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let removed = unresolved
+        .into_iter()
+        .filter(|(pos, _)| placed.iter().all(|(p, _)| p != pos))
+        .flat_map(|(pos, set)| set.into_iter().map(move |n| (pos, n)))
+        .filter(|&(pos, n)| {
+            outcomes
+                .iter()
+                .all(|outcome| !outcome.get_cell(pos).to_possibles().contains(n))
+        })
+        .collect();
+    (removed, placed)
+}
+
+/* Every number of a cell, or every place for a number required in a row or column */
+fn branches(grid: &Grid, size: usize) -> Vec<Vec<Candidate>> {
+    let cells = grid
+        .iter_by_indeterminates()
+        .into_iter()
+        .filter(|(_, set)| set.len() == size)
+        .map(|(pos, set)| set.into_iter().map(|n| (pos, n)).collect());
+    let units = grid.iter_by_rows_and_cols().into_iter().flat_map(|(vertical, line)| {
+        grid.requirements(vertical, line[0].0)
+            .into_iter()
+            .map(|n| {
+                line.iter()
+                    .filter(|(_, cell)| cell.to_unresolved().contains(n))
+                    .map(|&(pos, _)| (pos, n))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|places| places.len() == size)
+            .collect::<Vec<_>>()
+    });
+    cells.chain(units).collect()
+}
+
+/* Forcing chains: try out each branch of a cell or a required number, and keep whatever all of them agree on */
+pub fn forcing_chain(grid: &mut Grid) -> StrategyReturn {
+    for size in 2..=MAX_BRANCHES {
+        for branch in branches(grid, size) {
+            let (removed, placed) = common_conclusions(grid, &branch);
+            if removed.is_empty() && placed.is_empty() {
+                continue;
+            }
+            for &(pos, n) in &removed {
+                grid.set_impossible(pos, n)?;
+            }
+            for &(pos, n) in &placed {
+                grid.set_cell(pos, Solution(n));
+            }
+            return Ok(Some(SolveResults {
+                ty: ForcingChain(branch.clone(), removed, placed),
+                meta: SolveMetadata {
+                    colors: vec![branch],
+                    ..Default::default()
+                },
+            }));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::Strategy;
+    use crate::utils::*;
+
+    #[test]
+    fn test_cell_forcing_chain() {
+        let mut grid = g("
+#..5..
+5...2.
+#.f#..
+..#a.#
+4..3..
+.4...#
+");
+        let strats = StrategyList::no_guesses().except(&[Strategy::ForcingChain, Strategy::UniqueRequirement]);
+        while run_strat(&mut grid, &strats).is_ok() {}
+        assert_eq!(grid.cells[1][3], det([4, 6]));
+        assert_eq!(grid.cells[1][5], det([1, 3, 4, 6]));
+
+        /* 6 in (3, 1) rules out 6 in (5, 1) directly, and 4 there does so through the basic strategies */
+        assert_eq!(
+            forcing_chain(&mut grid),
+            Ok(Some(SolveResults {
+                ty: ForcingChain(vec![((3, 1), 4), ((3, 1), 6)], vec![((5, 1), 6)], vec![]),
+                meta: SolveMetadata {
+                    colors: vec![vec![((3, 1), 4), ((3, 1), 6)]],
+                    ..Default::default()
+                }
+            }))
+        );
+        assert_eq!(grid.cells[1][5], det([1, 3, 4]));
+    }
+
+    #[test]
+    fn test_forcing_chain_placements() {
+        let mut grid = g("
+#..5..
+5...2.
+#.f#..
+..#a.#
+4..3..
+.4...#
+");
+        let strats = StrategyList::new(&[Strategy::UpdateImpossibles]);
+        while run_strat(&mut grid, &strats).is_ok() {}
+
+        /* both numbers in (3, 1) fill in the bottom left corner the same way */
+        let res = forcing_chain(&mut grid).unwrap().unwrap();
+        let ForcingChain(branches, _, placed) = res.ty else {
+            panic!("{:?}", res.ty);
+        };
+        assert_eq!(branches, vec![((3, 1), 4), ((3, 1), 6)]);
+        assert_eq!(
+            placed,
+            vec![
+                ((0, 3), 2),
+                ((1, 3), 3),
+                ((2, 4), 2),
+                ((0, 5), 3),
+                ((2, 5), 1),
+                ((3, 5), 2),
+                ((4, 5), 5)
+            ]
+        );
+        assert_eq!(grid.to_string(), "#..5..\n5...2.\n#.f#..\n23#a.#\n4.23..\n34125#");
+    }
+}
@@ -3,8 +3,8 @@ use crate::grid::Cell::*;
 use crate::grid::{Grid, Point};
 use crate::solve_result::SolveType::*;
 use crate::solve_result::{into_ty, SolveResults, ValidationError, ValidationResult};
-use crate::solver::solve_round;
-use crate::strategy::StrategyReturn;
+use crate::solver::run_strat;
+use crate::strategy::{Strategy, StrategyList, StrategyReturn};
 use itertools::Itertools;
 use std::collections::VecDeque;
 use std::rc::Rc;
@@ -14,14 +14,14 @@ type ForcedNumber = (Point, u8);
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct GuessResult(pub ForcedNumber);
 
-fn run_guess(candidates: Vec<(Grid, ForcedNumber)>, max_depth: usize) -> Option<GuessResult> {
+fn run_guess(candidates: Vec<(Grid, ForcedNumber)>, max_depth: usize, strats: &StrategyList) -> Option<GuessResult> {
     let mut candidates: VecDeque<((Grid, ForcedNumber), usize)> = candidates.into_iter().map(|c| (c, 0)).collect();
 
     while let Some(((mut temp_grid, (pos, n)), count)) = candidates.pop_front() {
         if count > max_depth {
             continue;
         }
-        match into_ty(solve_round(&mut temp_grid, false)) {
+        match into_ty(run_strat(&mut temp_grid, strats)) {
             Err(ValidationError::OutOfStrats) => {}
             Ok(PuzzleSolved) => {} // well...
             Ok(_) => candidates.push_back(((temp_grid, (pos, n)), count + 1)),
@@ -32,7 +32,7 @@ fn run_guess(candidates: Vec<(Grid, ForcedNumber)>, max_depth: usize) -> Option<
     None
 }
 
-pub fn gather_and_run_guess<F>(grid: &Grid, filter: F, max_depth: usize) -> Option<GuessResult>
+pub fn gather_and_run_guess<F>(grid: &Grid, filter: F, max_depth: usize, strats: &StrategyList) -> Option<GuessResult>
 where
     F: Fn(BitSet) -> bool,
 {
@@ -52,24 +52,31 @@ where
         }
     }
 
-    run_guess(candidates, max_depth)
+    run_guess(candidates, max_depth, strats)
 }
 
 type GuessStepRes = (Point, u8, Vec<(Grid, SolveResults)>, Grid);
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct GuessSolveResult(pub GuessStepRes);
 
+/* The branches are followed with the caller's strategies, except for guessing */
 #[allow(clippy::type_complexity)]
-pub fn guess(grid: &mut Grid) -> StrategyReturn {
+pub fn guess(grid: &mut Grid, strats: &StrategyList) -> StrategyReturn {
     let mut temp_grid = grid.clone();
     let num_count = grid.x;
+    let inner = strats.except(&[
+        Strategy::UniqueRequirementGuess,
+        Strategy::ForcingChain,
+        Strategy::Guess,
+        Strategy::EnumerateSolutions,
+    ]);
 
     if let Some(res) = {
         (2..=3)
-            .filter_map(|set_size| gather_and_run_guess(grid, |set| set.len() == set_size, 25))
+            .filter_map(|set_size| gather_and_run_guess(grid, |set| set.len() == set_size, 25, &inner))
             .chain(
                 (2..=num_count)
-                    .filter_map(|set_size| gather_and_run_guess(grid, |set| set.len() == set_size, usize::MAX)),
+                    .filter_map(|set_size| gather_and_run_guess(grid, |set| set.len() == set_size, usize::MAX, &inner)),
             )
             .next()
     } {
@@ -79,7 +86,7 @@ pub fn guess(grid: &mut Grid) -> StrategyReturn {
         let mut steps: Vec<(Grid, SolveResults)> = vec![(temp_grid.clone(), StartGuess((x, y), n).into())];
 
         loop {
-            match solve_round(&mut temp_grid, false) {
+            match run_strat(&mut temp_grid, &inner) {
                 Err(ValidationResult {
                     ty: ValidationError::OutOfStrats,
                     meta: _,
@@ -127,7 +134,7 @@ mod tests {
         assert_eq!(setti(&mut grid), Ok(Some(Setti(set([5])).into())));
         assert_eq!(solve_basic(&mut grid), Err(OutOfStrats));
 
-        let res = guess(&mut grid);
+        let res = guess(&mut grid, &StrategyList::all());
         let (pos, n, _, _) = match res {
             Ok(Some(SolveResults {
                 ty: GuessStep(pos, n, steps, grid),
@@ -148,6 +155,6 @@ mod tests {
 ..
 ");
 
-        assert_eq!(Ok(None), guess(&mut grid));
+        assert_eq!(Ok(None), guess(&mut grid, &StrategyList::all()));
     }
 }
@@ -0,0 +1,139 @@
+use crate::bitset::BitSet;
+use crate::grid::{Grid, Point};
+use crate::solve_result::SolveType::HiddenSets;
+use crate::solve_result::{SolveMetadata, SolveResults};
+use crate::strategy::StrategyReturn;
+use crate::strats::{required_by_range, required_in_compartment_by_range};
+use itertools::Itertools;
+
+/* Unresolved cells of a compartment, row or column together with the numbers it still has to place. A compartment
+ * requires the middle of its straight range, a line its requirements and whatever its compartments require. */
+fn units(grid: &Grid) -> Vec<(Vec<(Point, BitSet)>, BitSet)> {
+    let compartments = grid.iter_by_compartments().into_iter().map(|compartment| {
+        let placed: BitSet = compartment
+            .cells
+            .iter()
+            .filter_map(|(_, cell)| cell.to_req_or_sol())
+            .collect();
+        let required = required_in_compartment_by_range(grid.x, &compartment).difference(placed);
+        (compartment.to_unresolved(), required)
+    });
+    let lines = grid.iter_by_rows_and_cols().into_iter().map(|(vertical, line)| {
+        let placed: BitSet = line.iter().filter_map(|(_, cell)| cell.to_req_or_sol()).collect();
+        let required = grid
+            .requirements(vertical, line[0].0)
+            .union(required_by_range(grid.x, &line))
+            .difference(placed);
+        let unresolved = line
+            .into_iter()
+            .map(|(pos, cell)| (pos, cell.to_unresolved()))
+            .filter(|(_, set)| !set.is_empty())
+            .collect();
+        (unresolved, required)
+    });
+    compartments.chain(lines).collect()
+}
+
+/* Hidden sets: if n numbers required in a compartment or a line fit only in n of its cells, those cells can't
+ * contain anything else */
+pub fn hidden_sets(grid: &mut Grid) -> StrategyReturn {
+    for (cells, required) in units(grid) {
+        for n in 2..cells.len() {
+            for numbers in required.into_iter().combinations(n) {
+                let numbers: BitSet = numbers.into_iter().collect();
+                let places = cells
+                    .iter()
+                    .filter(|(_, set)| !set.intersection(numbers).is_empty())
+                    .collect::<Vec<_>>();
+                if places.len() != n || places.iter().all(|(_, set)| set.difference(numbers).is_empty()) {
+                    continue;
+                }
+
+                for &&(pos, set) in &places {
+                    for num in set.difference(numbers) {
+                        grid.set_impossible(pos, num)?;
+                    }
+                }
+                let places = places.into_iter().map(|&(pos, _)| pos).collect::<Vec<_>>();
+                let colors = vec![places
+                    .iter()
+                    .flat_map(|&pos| numbers.into_iter().map(move |num| (pos, num)))
+                    .filter(|&((x, y), num)| grid.cells[y][x].to_unresolved().contains(num))
+                    .collect()];
+                return Ok(Some(SolveResults {
+                    ty: HiddenSets(places, numbers),
+                    meta: SolveMetadata {
+                        colors,
+                        ..Default::default()
+                    },
+                }));
+            }
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::*;
+
+    #[test]
+    fn test_hidden_sets() {
+        let mut grid = g("
+#####
+#....
+#####
+#####
+#####
+");
+        grid.cells[1][1] = det([1, 2, 5]);
+        grid.cells[1][2] = det([1, 2, 5]);
+        grid.cells[1][3] = det([1, 3, 4, 5]);
+        grid.cells[1][4] = det([1, 3, 4]);
+
+        /* the compartment is either 1-4 or 2-5, so it needs 3 and 4, and only (3, 1) and (4, 1) can take them */
+        assert_eq!(
+            hidden_sets(&mut grid),
+            Ok(Some(SolveResults {
+                ty: HiddenSets(vec![(3, 1), (4, 1)], set([3, 4])),
+                meta: SolveMetadata {
+                    colors: vec![vec![((3, 1), 3), ((3, 1), 4), ((4, 1), 3), ((4, 1), 4)]],
+                    ..Default::default()
+                }
+            }))
+        );
+        assert_eq!(grid.cells[1][3], det([3, 4]));
+        assert_eq!(grid.cells[1][4], det([3, 4]));
+        assert_eq!(grid.cells[1][1], det([1, 2, 5]));
+    }
+
+    #[test]
+    fn test_hidden_sets_in_line() {
+        let mut grid = g("
+#####
+.#...
+#####
+#####
+#####
+");
+        grid.cells[1][0] = det([1, 3, 5]);
+        grid.cells[1][2] = det([2, 3, 4]);
+        grid.cells[1][3] = det([2, 3, 4]);
+        grid.cells[1][4] = det([1, 2, 3, 4, 5]);
+        grid.row_requirements[1] = set([1, 5]);
+
+        assert_eq!(
+            hidden_sets(&mut grid),
+            Ok(Some(SolveResults {
+                ty: HiddenSets(vec![(0, 1), (4, 1)], set([1, 5])),
+                meta: SolveMetadata {
+                    colors: vec![vec![((0, 1), 1), ((0, 1), 5), ((4, 1), 1), ((4, 1), 5)]],
+                    ..Default::default()
+                }
+            }))
+        );
+        assert_eq!(grid.cells[1][0], det([1, 5]));
+        assert_eq!(grid.cells[1][4], det([1, 5]));
+    }
+}
@@ -6,8 +6,10 @@ use crate::strategy::StrategyReturn;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::hash::Hash;
 
-type Pairs = HashMap<(Point, u8), BTreeSet<Point>>;
-fn gather_pairs(grid: &Grid) -> Pairs {
+pub(crate) type Pairs = HashMap<(Point, u8), BTreeSet<Point>>;
+
+/* Strong links: a number required in a row or column which only two cells there can hold */
+pub(crate) fn gather_pairs(grid: &Grid) -> Pairs {
     let mut res = HashMap::new();
 
     for (pos, set) in grid.iter_by_indeterminates() {
@@ -70,6 +72,7 @@ fn split(colors: &Colors) -> SolveResults {
         ty: Medusa,
         meta: SolveMetadata {
             colors: vec![left, right],
+            ..Default::default()
         },
     }
 }
@@ -365,7 +368,8 @@ c.....e..
                             ((6, 3), 6),
                             ((6, 4), 5)
                         ]
-                    ]
+                    ],
+                    ..Default::default()
                 }
             })),
             medusa(&mut grid)
@@ -426,7 +430,8 @@ c.....e..
                     colors: vec![
                         vec![(a, 4), (c, 5), (b, 7), (d, 5), (f, 5), (f, 7), (e, 6)],
                         vec![(c, 4), (b, 4), (d, 7), (e, 5)]
-                    ]
+                    ],
+                    ..Default::default()
                 }
             })),
             medusa(&mut grid)
@@ -486,7 +491,8 @@ c.....e..
                     colors: vec![
                         vec![((0, 0), 4), ((0, 4), 5), ((4, 0), 7), ((6, 4), 7)],
                         vec![((0, 4), 4), ((4, 0), 4), ((4, 3), 7), ((6, 3), 7), ((6, 4), 5)]
-                    ]
+                    ],
+                    ..Default::default()
                 }
             })),
             medusa(&mut grid)
@@ -546,7 +552,8 @@ c.....e..
                     colors: vec![
                         vec![((0, 0), 4), ((0, 4), 5), ((4, 0), 7), ((4, 3), 5)],
                         vec![((0, 4), 4), ((4, 0), 4), ((4, 3), 7), ((6, 3), 5), ((6, 4), 5)]
-                    ]
+                    ],
+                    ..Default::default()
                 }
             })),
             medusa(&mut grid)
@@ -597,7 +604,8 @@ c.....e..
                     colors: vec![
                         vec![((1, 1), 2), ((1, 4), 3), ((3, 1), 3)],
                         vec![((1, 4), 2), ((3, 1), 2), ((3, 4), 3)]
-                    ]
+                    ],
+                    ..Default::default()
                 }
             })),
             medusa(&mut grid)
@@ -649,7 +657,8 @@ c.....e..
                     colors: vec![
                         vec![((1, 1), 2), ((1, 4), 4), ((3, 4), 2)],
                         vec![((1, 4), 2), ((3, 1), 2)]
-                    ]
+                    ],
+                    ..Default::default()
                 }
             })),
             medusa(&mut grid)
@@ -715,7 +724,8 @@ c.....e..
                             ((6, 4), 8)
                         ],
                         vec![((0, 4), 3), ((4, 0), 3), ((4, 3), 7), ((6, 4), 1)]
-                    ]
+                    ],
+                    ..Default::default()
                 }
             })),
             medusa(&mut grid)
@@ -772,7 +782,8 @@ c.....e..
                     colors: vec![
                         vec![((0, 0), 3), ((0, 4), 1), ((4, 0), 7), ((4, 3), 8), ((6, 4), 8)],
                         vec![((0, 4), 3), ((4, 0), 3), ((4, 3), 7), ((6, 4), 1)]
-                    ]
+                    ],
+                    ..Default::default()
                 }
             })),
             medusa(&mut grid)
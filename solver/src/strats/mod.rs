@@ -1,8 +1,19 @@
+use crate::grid::Cell::Solution;
+use crate::grid::Grid;
+use crate::solve_result::SolveType::PuzzleSolved;
+use crate::solve_result::ValidationError;
+use crate::solver::run_strat;
+use crate::strategy::{Strategy, StrategyList};
+
+mod aic;
 mod definite_min_max;
 mod enumerate_solutions;
 mod fish;
+mod forcing_chain;
 mod guess;
+mod hidden_sets;
 mod medusa;
+mod pattern_overlay;
 mod required_and_forbidden;
 mod required_range;
 mod row_col_brute;
@@ -14,13 +25,19 @@ mod trivial;
 mod unique_requirement;
 mod update_impossibles;
 mod update_required_and_forbidden;
+mod x_chain;
+mod xy_chain;
 mod y_wing;
 
+pub use aic::*;
 pub use definite_min_max::*;
 pub use enumerate_solutions::*;
 pub use fish::*;
+pub use forcing_chain::*;
 pub use guess::*;
+pub use hidden_sets::*;
 pub use medusa::*;
+pub use pattern_overlay::*;
 pub use required_and_forbidden::*;
 pub use required_range::*;
 pub use row_col_brute::*;
@@ -32,4 +49,28 @@ pub use trivial::*;
 pub use unique_requirement::*;
 pub use update_impossibles::*;
 pub use update_required_and_forbidden::*;
+pub use x_chain::*;
+pub use xy_chain::*;
 pub use y_wing::*;
+
+/* The grid after placing the candidates and running the obvious compartment reductions, or None if that runs
+ * into a conflict */
+pub(crate) fn basic_consequences(grid: &Grid, candidates: &[Candidate]) -> Option<Grid> {
+    let strats = StrategyList::new(&[
+        Strategy::UpdateImpossibles,
+        Strategy::Stranded,
+        Strategy::DefiniteMinMax,
+    ]);
+    let mut grid = grid.clone();
+    for &(pos, n) in candidates {
+        grid.set_cell(pos, Solution(n));
+    }
+    loop {
+        match run_strat(&mut grid, &strats) {
+            Ok(res) if res.ty == PuzzleSolved => return Some(grid),
+            Ok(_) => {}
+            Err(e) if e.ty == ValidationError::OutOfStrats => return Some(grid),
+            Err(_) => return None,
+        }
+    }
+}
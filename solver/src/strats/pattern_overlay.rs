@@ -0,0 +1,191 @@
+use crate::grid::Cell::*;
+use crate::grid::{Grid, Point};
+use crate::solve_result::SolveType::PatternOverlay;
+use crate::solve_result::{SolveMetadata, SolveResults};
+use crate::strategy::StrategyReturn;
+
+/* Column of the number in a row, None for a row without it */
+type Choice = Option<usize>;
+
+fn step(mask: usize, choice: Choice) -> Option<usize> {
+    match choice {
+        None => Some(mask),
+        Some(x) if mask & (1 << x) == 0 => Some(mask | (1 << x)),
+        Some(_) => None,
+    }
+}
+
+fn choices(grid: &Grid, num: u8) -> Vec<Vec<Choice>> {
+    let open_cols = (0..grid.x)
+        .filter(|&x| !grid.col_forbidden[x].contains(num) && !(0..grid.y).any(|y| grid.cells[y][x] == Blocker(num)))
+        .collect::<Vec<_>>();
+
+    grid.cells
+        .iter()
+        .enumerate()
+        .map(|(y, row)| {
+            if let Some(x) = row
+                .iter()
+                .position(|cell| matches!(cell, Solution(n) | Requirement(n) if *n == num))
+            {
+                return vec![Some(x)];
+            }
+            let mut choices = vec![];
+            if !grid.row_requirements[y].contains(num) {
+                choices.push(None);
+            }
+            if !grid.row_forbidden[y].contains(num) && !row.contains(&Blocker(num)) {
+                choices.extend(
+                    open_cols
+                        .iter()
+                        .filter(|&&x| row[x].to_unresolved().contains(num))
+                        .map(|&x| Some(x)),
+                );
+            }
+            choices
+        })
+        .collect()
+}
+
+/* For each row, the choices which some placement pattern of the number takes. A pattern has the number at most
+ * once in each row and column and exactly once in the rows and columns requiring it. Returns None if there are
+ * no patterns at all. */
+fn used_choices(grid: &Grid, num: u8, choices: &[Vec<Choice>]) -> Option<Vec<Vec<Choice>>> {
+    let masks = 1 << grid.x;
+    let required = (0..grid.x)
+        .filter(|&x| grid.col_requirements[x].contains(num))
+        .fold(0, |mask, x| mask | (1 << x));
+
+    /* column masks reachable from the top, and those from which the rest of the rows can be completed */
+    let mut reachable = vec![vec![false; masks]; grid.y + 1];
+    reachable[0][0] = true;
+    for y in 0..grid.y {
+        for mask in 0..masks {
+            if reachable[y][mask] {
+                for next in choices[y].iter().filter_map(|&choice| step(mask, choice)) {
+                    reachable[y + 1][next] = true;
+                }
+            }
+        }
+    }
+    let mut completes = vec![vec![false; masks]; grid.y + 1];
+    for (mask, completes) in completes[grid.y].iter_mut().enumerate() {
+        *completes = mask & required == required;
+    }
+    for y in (0..grid.y).rev() {
+        for mask in 0..masks {
+            completes[y][mask] = choices[y]
+                .iter()
+                .any(|&choice| step(mask, choice).is_some_and(|next| completes[y + 1][next]));
+        }
+    }
+    if !completes[0][0] {
+        return None;
+    }
+
+    Some(
+        choices
+            .iter()
+            .enumerate()
+            .map(|(y, row)| {
+                row.iter()
+                    .copied()
+                    .filter(|&choice| {
+                        (0..masks).any(|mask| {
+                            reachable[y][mask] && step(mask, choice).is_some_and(|next| completes[y + 1][next])
+                        })
+                    })
+                    .collect()
+            })
+            .collect(),
+    )
+}
+
+/* Pattern overlay: a number can be removed from cells which no placement pattern uses, and placed in cells which
+ * every pattern uses */
+pub fn pattern_overlay(grid: &mut Grid) -> StrategyReturn {
+    for num in 1..=grid.x as u8 {
+        let choices = choices(grid, num);
+        let Some(used) = used_choices(grid, num, &choices) else {
+            continue;
+        };
+
+        let mut removed: Vec<Point> = vec![];
+        let mut placed: Vec<Point> = vec![];
+        for (y, (row, used)) in choices.iter().zip(&used).enumerate() {
+            for &x in row.iter().flatten() {
+                if !matches!(grid.cells[y][x], Indeterminate(_)) {
+                    continue;
+                }
+                if !used.contains(&Some(x)) {
+                    removed.push((x, y));
+                } else if used.len() == 1 {
+                    placed.push((x, y));
+                }
+            }
+        }
+        if removed.is_empty() && placed.is_empty() {
+            continue;
+        }
+
+        for &pos in &removed {
+            grid.set_impossible(pos, num)?;
+        }
+        for &pos in &placed {
+            grid.set_cell(pos, Solution(num));
+        }
+        let meta = SolveMetadata {
+            colors: vec![
+                placed.iter().map(|&pos| (pos, num)).collect(),
+                removed.iter().map(|&pos| (pos, num)).collect(),
+            ],
+            ..Default::default()
+        };
+        return Ok(Some(SolveResults {
+            ty: PatternOverlay(num, removed, placed),
+            meta,
+        }));
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strats::update_required_and_forbidden;
+    use crate::utils::*;
+
+    #[test]
+    fn test_pattern_overlay() {
+        let mut grid = g("
+....
+....
+....
+....
+");
+        grid.cells[0][2] = det([2, 3, 4]);
+        grid.cells[0][3] = det([2, 3, 4]);
+        grid.cells[1][2] = det([2, 3, 4]);
+        grid.cells[1][3] = det([2, 3, 4]);
+        grid.cells[2][3] = det([2, 3, 4]);
+        update_required_and_forbidden(&mut grid).unwrap();
+
+        /* rows 0 and 1 take columns 0 and 1, which leaves only (2, 2) for row 2 and (3, 3) for row 3 */
+        assert_eq!(
+            pattern_overlay(&mut grid),
+            Ok(Some(SolveResults {
+                ty: PatternOverlay(1, vec![(0, 2), (1, 2), (0, 3), (1, 3), (2, 3)], vec![(2, 2), (3, 3)]),
+                meta: SolveMetadata {
+                    colors: vec![
+                        vec![((2, 2), 1), ((3, 3), 1)],
+                        vec![((0, 2), 1), ((1, 2), 1), ((0, 3), 1), ((1, 3), 1), ((2, 3), 1)]
+                    ],
+                    ..Default::default()
+                }
+            }))
+        );
+        assert_eq!(grid.cells[2][2], Solution(1));
+        assert_eq!(grid.cells[3][2], det([2, 3, 4]));
+    }
+}
@@ -1,161 +1,297 @@
 use crate::bitset::BitSet;
-use crate::grid::{Cell, Compartment, Grid};
+use crate::grid::Grid;
 use crate::solve_result::SolveType::RowColBrute;
+use crate::solve_result::ValidationError;
 use crate::strategy::StrategyReturn;
-use crate::validator::compartment_valid;
-
-pub fn solution_valid(compartments: &[Compartment], requirements: BitSet) -> bool {
-    let mut seen_numbers = BitSet::new();
-    for compartment in compartments {
-        for (_, cell) in &compartment.cells {
-            match cell {
-                Cell::Black => {}
-                Cell::Indeterminate(_) => {}
-                Cell::Solution(n) | Cell::Blocker(n) | Cell::Requirement(n) => {
-                    if seen_numbers.contains(*n) {
-                        return false;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::cell::RefCell;
+
+/* The brute force solve_line replaced. It is only kept to check the line solver against, in the tests and the
+ * line_solver bench. */
+#[cfg(any(test, feature = "bench"))]
+mod reference {
+    use crate::bitset::BitSet;
+    use crate::grid::{Cell, Compartment};
+    use crate::validator::compartment_valid;
+
+    fn solution_valid(compartments: &[Compartment], requirements: BitSet) -> bool {
+        let mut seen_numbers = BitSet::new();
+        for compartment in compartments {
+            for (_, cell) in &compartment.cells {
+                match cell {
+                    Cell::Black => {}
+                    Cell::Indeterminate(_) => {}
+                    Cell::Solution(n) | Cell::Blocker(n) | Cell::Requirement(n) => {
+                        if seen_numbers.contains(*n) {
+                            return false;
+                        }
+                        seen_numbers.insert(*n);
                     }
-                    seen_numbers.insert(*n);
                 }
             }
         }
-    }
 
-    if !requirements.is_subset(seen_numbers) {
-        return false;
-    }
+        if !requirements.is_subset(seen_numbers) {
+            return false;
+        }
 
-    compartments
-        .iter()
-        .all(|compartment| compartment_valid(compartment).is_ok())
-}
+        compartments
+            .iter()
+            .all(|compartment| compartment_valid(compartment).is_ok())
+    }
 
-pub fn compartment_solutions(compartments: &[Compartment], requirements: BitSet) -> Vec<Vec<Compartment>> {
-    let mut available_solutions = Vec::new();
-    let mut all_solved = true;
-    'outer: for (index, compartment) in compartments.iter().enumerate() {
-        for (cell_index, cell) in compartment.cells.iter().enumerate() {
-            if let (loc, Cell::Indeterminate(set)) = cell {
-                if set.is_empty() {
-                    return vec![];
-                }
-                all_solved = false;
-                for n in *set {
-                    let mut with_solution = compartments.to_vec();
-                    with_solution[index].cells[cell_index] = (*loc, Cell::Solution(n));
-                    for compartment in &mut with_solution {
-                        for i in 0..compartment.cells.len() {
-                            if let Cell::Indeterminate(set) = &mut compartment.cells[i].1 {
-                                set.remove(n);
+    pub fn compartment_solutions(compartments: &[Compartment], requirements: BitSet) -> Vec<Vec<Compartment>> {
+        let mut available_solutions = Vec::new();
+        let mut all_solved = true;
+        'outer: for (index, compartment) in compartments.iter().enumerate() {
+            for (cell_index, cell) in compartment.cells.iter().enumerate() {
+                if let (loc, Cell::Indeterminate(set)) = cell {
+                    if set.is_empty() {
+                        return vec![];
+                    }
+                    all_solved = false;
+                    for n in *set {
+                        let mut with_solution = compartments.to_vec();
+                        with_solution[index].cells[cell_index] = (*loc, Cell::Solution(n));
+                        for compartment in &mut with_solution {
+                            for i in 0..compartment.cells.len() {
+                                if let Cell::Indeterminate(set) = &mut compartment.cells[i].1 {
+                                    set.remove(n);
+                                }
                             }
                         }
+                        if compartment_valid(&with_solution[index]).is_ok() {
+                            available_solutions.append(&mut compartment_solutions(&with_solution, requirements))
+                        }
                     }
-                    if compartment_valid(&with_solution[index]).is_ok() {
-                        available_solutions.append(&mut compartment_solutions(&with_solution, requirements))
-                    }
+                    break 'outer;
                 }
-                break 'outer;
             }
         }
+        if all_solved && solution_valid(compartments, requirements) {
+            available_solutions.push(compartments.to_vec());
+        }
+        available_solutions
     }
-    if all_solved && solution_valid(compartments, requirements) {
-        available_solutions.push(compartments.to_vec());
-    }
-    available_solutions
 }
 
-fn compartment_contains_number(comp: &Compartment, num: u8) -> bool {
-    comp.cells.iter().any(|(_, cell)| cell.to_req_or_sol() == Some(num))
-}
+#[cfg(any(test, feature = "bench"))]
+pub use reference::compartment_solutions;
 
-fn solved_compartments_contains_number(compartments: &[Compartment], num: u8) -> bool {
-    compartments.iter().any(|comp| compartment_contains_number(comp, num))
+/* All straights of each length for numbers 1..=size, as masks in the BitSet layout */
+pub fn line_straights(size: usize) -> Vec<Vec<u32>> {
+    (0..=size)
+        .map(|len| match len {
+            0 => vec![],
+            _ => (1..=size + 1 - len).map(|start| ((1u32 << len) - 1) << start).collect(),
+        })
+        .collect()
 }
 
-pub fn row_col_brute(grid: &mut Grid) -> StrategyReturn {
-    let mut changes = false;
-
-    for (index, compartments) in grid.iter_by_row_compartments().into_iter().enumerate() {
-        if compartments.len() <= 1 {
-            continue;
-        }
-        let solutions = compartment_solutions(&compartments, grid.row_requirements[index]);
-        for i in 1..=9 {
-            if solutions
-                .iter()
-                .all(|comps| solved_compartments_contains_number(comps, i))
-            {
-                changes |= grid.row_requirements[index].insert(i);
-            }
-            if solutions
-                .iter()
-                .all(|comps| !solved_compartments_contains_number(comps, i))
-            {
-                changes |= grid.row_forbidden[index].insert(i);
-            }
-        }
+/* Picks one option from each layer so that no two picks share a number and the union of the picks is accepted.
+ * Returns which options are part of some such pick, by layer, and the possible unions. */
+fn layered_picks(layers: &[Vec<u32>], accept: impl Fn(u32) -> bool) -> (Vec<Vec<bool>>, Vec<u32>) {
+    let mut reachable = vec![vec![0u32]];
+    for options in layers {
+        let mut next = reachable[reachable.len() - 1]
+            .iter()
+            .flat_map(|&mask| {
+                options
+                    .iter()
+                    .filter(move |&&option| mask & option == 0)
+                    .map(move |&option| mask | option)
+            })
+            .collect::<Vec<_>>();
+        next.sort_unstable();
+        next.dedup();
+        reachable.push(next);
+    }
 
-        let mut sample_pos = None;
-        let mut new_cells = std::iter::repeat_n(BitSet::new(), grid.x).collect::<Vec<_>>();
-        for solution in solutions {
-            for compartment in solution {
-                for (pos, cell) in compartment.cells {
-                    if let Cell::Solution(n) = cell {
-                        sample_pos = Some(pos);
-                        new_cells[pos.0].insert(n);
-                    }
-                }
-            }
-        }
-        if let Some((_, y)) = sample_pos {
-            for (x, cell) in new_cells.into_iter().enumerate() {
-                for i in 1..9 {
-                    if !cell.contains(i) {
-                        grid.set_impossible((x, y), i)?;
+    let unions = reachable[layers.len()]
+        .iter()
+        .copied()
+        .filter(|&mask| accept(mask))
+        .collect::<Vec<_>>();
+    let mut completes = unions.iter().copied().collect::<FxHashSet<_>>();
+    let mut used = vec![vec![]; layers.len()];
+    for (index, options) in layers.iter().enumerate().rev() {
+        let mut previous = FxHashSet::default();
+        used[index] = options
+            .iter()
+            .map(|&option| {
+                let mut is_used = false;
+                for &mask in &reachable[index] {
+                    if mask & option == 0 && completes.contains(&(mask | option)) {
+                        previous.insert(mask);
+                        is_used = true;
                     }
                 }
-            }
-        }
+                is_used
+            })
+            .collect();
+        completes = previous;
     }
+    (used, unions)
+}
 
-    for (index, compartments) in grid.iter_by_col_compartments().into_iter().enumerate() {
-        if compartments.len() <= 1 {
-            continue;
-        }
-        let solutions = compartment_solutions(&compartments, grid.col_requirements[index]);
-        for i in 1..=9 {
-            if solutions
+/* The straights a compartment can form, each with the candidates its cells can take in that straight */
+fn compartment_straights(cells: &[BitSet], straights: &[u32]) -> Vec<(u32, Vec<BitSet>)> {
+    straights
+        .iter()
+        .filter_map(|&straight| {
+            let layers = cells
                 .iter()
-                .all(|comps| solved_compartments_contains_number(comps, i))
-            {
-                changes |= grid.col_requirements[index].insert(i);
+                .map(|set| {
+                    BitSet::new_from_number(set.to_number() & straight)
+                        .into_iter()
+                        .map(|n| 1u32 << n)
+                        .collect()
+                })
+                .collect::<Vec<Vec<u32>>>();
+            let (used, unions) = layered_picks(&layers, |_| true);
+            if unions.is_empty() {
+                return None;
             }
-            if solutions
+            let cells = layers
                 .iter()
-                .all(|comps| !solved_compartments_contains_number(comps, i))
-            {
-                changes |= grid.col_forbidden[index].insert(i);
-            }
+                .zip(used)
+                .map(|(options, used)| {
+                    let mask = options
+                        .iter()
+                        .zip(used)
+                        .filter(|(_, used)| *used)
+                        .fold(0, |acc, (option, _)| acc | option);
+                    BitSet::new_from_number(mask)
+                })
+                .collect();
+            Some((straight, cells))
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineSolution {
+    /* candidates of each cell, by compartment */
+    pub cells: Vec<Vec<BitSet>>,
+    /* numbers in every solution of the line */
+    pub required: BitSet,
+    /* numbers in no solution of the line */
+    pub forbidden: BitSet,
+}
+
+/* Solves a line given the candidates of its compartments' cells, with solved cells as single candidates. Each
+ * compartment picks one of its straights, and the straights may not overlap, contain forbidden numbers or leave
+ * out required ones. Returns None if the line has no solutions. */
+pub fn solve_line(
+    straights: &[Vec<u32>],
+    compartments: &[Vec<BitSet>],
+    requirements: BitSet,
+    forbidden: BitSet,
+) -> Option<LineSolution> {
+    let options = compartments
+        .iter()
+        .map(|cells| {
+            let mut options = compartment_straights(cells, straights.get(cells.len()).map_or(&[], |s| s));
+            options.retain(|(straight, _)| straight & forbidden.to_number() == 0);
+            options
+        })
+        .collect::<Vec<_>>();
+    let layers = options
+        .iter()
+        .map(|options| options.iter().map(|(straight, _)| *straight).collect())
+        .collect::<Vec<Vec<u32>>>();
+    let (used, unions) = layered_picks(&layers, |mask| requirements.is_subset(BitSet::new_from_number(mask)));
+    if unions.is_empty() {
+        return None;
+    }
+
+    let numbers = (1..straights.len() as u8).collect::<BitSet>();
+    let possible = unions.iter().fold(0, |acc, mask| acc | mask);
+    let required = unions.iter().fold(u32::MAX, |acc, mask| acc & mask);
+    let cells = options
+        .into_iter()
+        .zip(used)
+        .zip(compartments)
+        .map(|((options, used), cells)| {
+            options
+                .into_iter()
+                .zip(used)
+                .filter(|(_, used)| *used)
+                .fold(vec![BitSet::new(); cells.len()], |acc, ((_, sets), _)| {
+                    acc.into_iter().zip(sets).map(|(a, b)| a.union(b)).collect()
+                })
+        })
+        .collect();
+    Some(LineSolution {
+        cells,
+        required: BitSet::new_from_number(required),
+        forbidden: numbers.difference(BitSet::new_from_number(possible)),
+    })
+}
+
+type LineKey = (usize, Vec<Vec<BitSet>>, BitSet, BitSet);
+const LINE_CACHE_LIMIT: usize = 1 << 16;
+
+thread_local! {
+    static LINE_CACHE: RefCell<FxHashMap<LineKey, Option<LineSolution>>> = RefCell::new(FxHashMap::default());
+}
+
+/* solve_line, cached by the candidates, requirements and forbidden numbers of the line. Guesses and chains revisit
+ * the same lines over and over. */
+pub fn solve_line_cached(
+    straights: &[Vec<u32>],
+    compartments: &[Vec<BitSet>],
+    requirements: BitSet,
+    forbidden: BitSet,
+) -> Option<LineSolution> {
+    let key = (straights.len(), compartments.to_vec(), requirements, forbidden);
+    if let Some(solution) = LINE_CACHE.with(|cache| cache.borrow().get(&key).cloned()) {
+        return solution;
+    }
+    let solution = solve_line(straights, compartments, requirements, forbidden);
+    LINE_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.len() >= LINE_CACHE_LIMIT {
+            cache.clear();
         }
+        cache.insert(key, solution.clone());
+    });
+    solution
+}
 
-        let mut sample_pos = None;
-        let mut new_cells = std::iter::repeat_n(BitSet::new(), grid.y).collect::<Vec<_>>();
-        for solution in solutions {
-            for compartment in solution {
-                for (pos, cell) in compartment.cells {
-                    if let Cell::Solution(n) = cell {
-                        sample_pos = Some(pos);
-                        new_cells[pos.1].insert(n);
-                    }
-                }
+pub fn row_col_brute(grid: &mut Grid) -> StrategyReturn {
+    let straights = line_straights(grid.x);
+    let mut changes = false;
+
+    for vertical in [false, true] {
+        let lines = if vertical {
+            grid.iter_by_col_compartments()
+        } else {
+            grid.iter_by_row_compartments()
+        };
+        for compartments in lines {
+            if compartments.len() <= 1 {
+                continue;
             }
-        }
-        if let Some((x, _)) = sample_pos {
-            for (y, cell) in new_cells.into_iter().enumerate() {
-                for i in 1..9 {
-                    if !cell.contains(i) {
-                        grid.set_impossible((x, y), i)?;
-                    }
+            let sample_pos = compartments[0].cells[0].0;
+            let cells = compartments
+                .iter()
+                .map(|compartment| compartment.cells.iter().map(|(_, cell)| cell.to_possibles()).collect())
+                .collect::<Vec<Vec<_>>>();
+            let Some(solution) = solve_line_cached(
+                &straights,
+                &cells,
+                grid.requirements(vertical, sample_pos),
+                grid.forbidden(vertical, sample_pos),
+            ) else {
+                return Err(ValidationError::NoSolutions.into());
+            };
+
+            changes |= grid.requirements_mut(vertical, sample_pos).append(solution.required);
+            changes |= grid.forbidden_mut(vertical, sample_pos).append(solution.forbidden);
+            for (compartment, sets) in compartments.iter().zip(solution.cells) {
+                for ((pos, cell), set) in compartment.cells.iter().zip(sets) {
+                    changes |= grid.remove_numbers(*pos, cell.to_unresolved().difference(set))?;
                 }
             }
         }
@@ -171,9 +307,12 @@ pub fn row_col_brute(grid: &mut Grid) -> StrategyReturn {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::grid::{Cell, Compartment};
     use crate::solve_result::SolveType::RequiredAndForbidden;
     use crate::strats::update_required_and_forbidden;
     use crate::utils::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
 
     #[test]
     fn test_row_col_brute() {
@@ -249,4 +388,85 @@ mod tests {
         assert_eq!(grid.cells[0][2], Cell::Indeterminate(set([2, 3, 4, 5, 6])));
         assert_eq!(grid.cells[2][0], Cell::Indeterminate(set([2, 3, 4, 5, 6])));
     }
+
+    #[test]
+    fn test_solve_line_matches_compartment_solutions() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..500 {
+            let size = rng.random_range(1..=9);
+            let straights = line_straights(size);
+            let mut compartments = Vec::new();
+            let mut x = 0;
+            while x < size {
+                let len = rng.random_range(1..=(size - x).min(5));
+                let cells = (x..x + len)
+                    .map(|x| {
+                        let cell = if rng.random_bool(0.1) {
+                            Cell::Solution(rng.random_range(1..=size as u8))
+                        } else {
+                            Cell::Indeterminate((1..=size as u8).filter(|_| rng.random_bool(0.6)).collect())
+                        };
+                        ((x, 0), cell)
+                    })
+                    .collect();
+                compartments.push(Compartment { cells, vertical: false });
+                x += len + 1;
+            }
+            let requirements = (1..=size as u8).filter(|_| rng.random_bool(0.1)).collect::<BitSet>();
+            let forbidden = (1..=size as u8)
+                .filter(|&n| !requirements.contains(n) && rng.random_bool(0.1))
+                .collect::<BitSet>();
+
+            /* compartment_solutions doesn't know about forbidden numbers, so drop the solutions using them */
+            let solutions = compartment_solutions(&compartments, requirements)
+                .into_iter()
+                .filter(|solution| {
+                    solution
+                        .iter()
+                        .flat_map(|c| c.cells.iter().filter_map(|(_, cell)| cell.to_req_or_sol()))
+                        .all(|n| !forbidden.contains(n))
+                })
+                .collect::<Vec<_>>();
+            let cells = compartments
+                .iter()
+                .map(|compartment| compartment.cells.iter().map(|(_, cell)| cell.to_possibles()).collect())
+                .collect::<Vec<Vec<_>>>();
+            let Some(solution) = solve_line(&straights, &cells, requirements, forbidden) else {
+                assert!(solutions.is_empty());
+                continue;
+            };
+            assert!(!solutions.is_empty());
+
+            let numbers = solutions
+                .iter()
+                .map(|solution| {
+                    solution
+                        .iter()
+                        .flat_map(|c| c.cells.iter().filter_map(|(_, cell)| cell.to_req_or_sol()))
+                        .collect::<BitSet>()
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                solution.required,
+                (1..=size as u8)
+                    .filter(|&n| numbers.iter().all(|s| s.contains(n)))
+                    .collect()
+            );
+            assert_eq!(
+                solution.forbidden,
+                (1..=size as u8)
+                    .filter(|&n| numbers.iter().all(|s| !s.contains(n)))
+                    .collect()
+            );
+            for (index, sets) in solution.cells.iter().enumerate() {
+                for (cell_index, set) in sets.iter().enumerate() {
+                    let expected = solutions
+                        .iter()
+                        .filter_map(|solution| solution[index].cells[cell_index].1.to_req_or_sol())
+                        .collect::<BitSet>();
+                    assert_eq!(*set, expected);
+                }
+            }
+        }
+    }
 }
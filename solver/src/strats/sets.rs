@@ -64,7 +64,10 @@ pub fn sets(grid: &mut Grid) -> Result<Option<SolveResults>, ValidationResult> {
                         .collect()
                 })
                 .collect();
-            let meta = SolveMetadata { colors };
+            let meta = SolveMetadata {
+                colors,
+                ..Default::default()
+            };
             let ty = SolveType::Sets(n);
             return Ok(Some(SolveResults { ty, meta }));
         }
@@ -100,7 +103,8 @@ mod tests {
             Ok(Some(SolveResults {
                 ty: Sets(2),
                 meta: SolveMetadata {
-                    colors: vec![vec![((1, 1), 1), ((2, 1), 1), ((1, 1), 2), ((2, 1), 2)]]
+                    colors: vec![vec![((1, 1), 1), ((2, 1), 1), ((1, 1), 2), ((2, 1), 2)]],
+                    ..Default::default()
                 }
             })),
             sets(&mut grid)
@@ -138,7 +142,8 @@ mod tests {
                         ((1, 1), 3),
                         ((1, 2), 3),
                         ((1, 3), 3)
-                    ]]
+                    ]],
+                    ..Default::default()
                 }
             })),
             sets(&mut grid)
@@ -185,7 +190,8 @@ mod tests {
             Ok(Some(SolveResults {
                 ty: Sets(2),
                 meta: SolveMetadata {
-                    colors: vec![vec![((1, 1), 1), ((2, 1), 1), ((1, 1), 2), ((2, 1), 2)]]
+                    colors: vec![vec![((1, 1), 1), ((2, 1), 1), ((1, 1), 2), ((2, 1), 2)]],
+                    ..Default::default()
                 }
             })),
             sets(&mut grid)
@@ -209,7 +215,8 @@ mod tests {
                         ((1, 1), 3),
                         ((1, 2), 3),
                         ((1, 3), 3)
-                    ]]
+                    ]],
+                    ..Default::default()
                 }
             })),
             sets(&mut grid)
@@ -9,7 +9,7 @@ pub fn update_impossibles(grid: &mut Grid) -> StrategyReturn {
         row_impossibles.push(
             row.into_iter()
                 .filter_map(|(_, c)| c.to_determinate())
-                .chain(grid.row_forbidden[y].into_iter())
+                .chain(grid.row_forbidden[y])
                 .collect(),
         );
     }
@@ -18,7 +18,7 @@ pub fn update_impossibles(grid: &mut Grid) -> StrategyReturn {
         col_impossibles.push(
             col.into_iter()
                 .filter_map(|(_, c)| c.to_determinate())
-                .chain(grid.col_forbidden[x].into_iter())
+                .chain(grid.col_forbidden[x])
                 .collect(),
         )
     }
@@ -0,0 +1,112 @@
+use crate::grid::{Grid, Point};
+use crate::solve_result::SolveType::XChain;
+use crate::solve_result::{SolveMetadata, SolveResults};
+use crate::strategy::StrategyReturn;
+use crate::strats::basic_consequences;
+use crate::strats::medusa::{gather_pairs, Pairs};
+use itertools::Itertools;
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, HashSet};
+
+/* Single-digit coloring. The strong links of one number split into two colors, one of which holds the number
+ * in every cell. Since all links run along rows and columns, the colors never see each other the way Sudoku
+ * boxes allow, so instead a color is tried out: if placing the number in all of its cells breaks a
+ * compartment, the number is removed from those cells. */
+
+type Colors = BTreeMap<Point, bool>;
+fn color_chain(pairs: &Pairs, start: Point, num: u8) -> Colors {
+    let mut colors: Colors = [(start, false)].into_iter().collect();
+    let mut queue = vec![start];
+    while let Some(pos) = queue.pop() {
+        let color = colors[&pos];
+        for &other in pairs.get(&(pos, num)).into_iter().flatten() {
+            if let Entry::Vacant(entry) = colors.entry(other) {
+                entry.insert(!color);
+                queue.push(other);
+            }
+        }
+    }
+    colors
+}
+
+fn cells_with_color(colors: &Colors, color: bool) -> Vec<Point> {
+    colors
+        .iter()
+        .filter(|(_, &c)| c == color)
+        .map(|(&pos, _)| pos)
+        .collect()
+}
+
+pub fn x_chain(grid: &mut Grid) -> StrategyReturn {
+    let pairs = gather_pairs(grid);
+    let mut colored = HashSet::new();
+    for (start, num) in pairs.keys().copied().sorted() {
+        if colored.contains(&(start, num)) {
+            continue;
+        }
+        let colors = color_chain(&pairs, start, num);
+        colored.extend(colors.keys().map(|&pos| (pos, num)));
+
+        for color in [false, true] {
+            let cells = cells_with_color(&colors, color);
+            let placed = cells.iter().map(|&pos| (pos, num)).collect::<Vec<_>>();
+            if basic_consequences(grid, &placed).is_some() {
+                continue;
+            }
+            for &pos in &cells {
+                grid.set_impossible(pos, num)?;
+            }
+            let chain = [color, !color]
+                .into_iter()
+                .map(|c| cells_with_color(&colors, c).into_iter().map(|pos| (pos, num)).collect())
+                .collect();
+            return Ok(Some(SolveResults {
+                ty: XChain(num, cells),
+                meta: SolveMetadata {
+                    colors: chain,
+                    ..Default::default()
+                },
+            }));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::run_strat;
+    use crate::strategy::StrategyList;
+    use crate::utils::*;
+
+    #[test]
+    fn test_x_chain() {
+        let mut grid = g("
+.#6#23...
+.........
+##...4...
+.#8a..3..
+..#...#..
+..3..f9#.
+...8...##
+.........
+...74#8#.
+");
+        let strats = StrategyList::for_difficulty(5);
+        while run_strat(&mut grid, &strats).is_ok() {}
+        assert_eq!(grid.cells[1][2], det([7, 9]));
+
+        /* either (2, 1) or (2, 2) is 7, and with 7 in (2, 1) the basic reductions empty (7, 2) */
+        assert_eq!(
+            x_chain(&mut grid),
+            Ok(Some(SolveResults {
+                ty: XChain(7, vec![(2, 1)]),
+                meta: SolveMetadata {
+                    colors: vec![vec![((2, 1), 7)], vec![((2, 2), 7)]],
+                    ..Default::default()
+                }
+            }))
+        );
+        assert_eq!(grid.cells[1][2], det([9]));
+    }
+}
@@ -0,0 +1,266 @@
+use crate::bitset::BitSet;
+use crate::grid::{Grid, Point};
+use crate::solve_result::SolveType::{XyChain, XyzWing};
+use crate::solve_result::{SolveMetadata, SolveResults, ValidationResult};
+use crate::strategy::StrategyReturn;
+use std::collections::{HashMap, VecDeque};
+
+pub type Candidate = (Point, u8);
+
+type CompartmentKey = (bool, Point, usize);
+
+/* Tells which candidates cannot both be true: two numbers in one cell, the same number twice in a row or
+ * column, or two numbers too far apart to be in the same compartment */
+pub(crate) struct Peers {
+    compartments: HashMap<Point, Vec<CompartmentKey>>,
+}
+
+impl Peers {
+    pub(crate) fn new(grid: &Grid) -> Peers {
+        let mut compartments: HashMap<Point, Vec<CompartmentKey>> = HashMap::new();
+        for compartment in grid.iter_by_compartments() {
+            let key = (compartment.vertical, compartment.sample_pos(), compartment.cells.len());
+            for (pos, _) in &compartment.cells {
+                compartments.entry(*pos).or_default().push(key);
+            }
+        }
+        Peers { compartments }
+    }
+
+    pub(crate) fn conflicts(&self, (pos1, n1): Candidate, (pos2, n2): Candidate) -> bool {
+        if pos1 == pos2 {
+            return n1 != n2;
+        }
+        if n1 == n2 && (pos1.0 == pos2.0 || pos1.1 == pos2.1) {
+            return true;
+        }
+        let distance = n1.abs_diff(n2) as usize;
+        let other = self.compartments.get(&pos2).map(|v| v.as_slice()).unwrap_or_default();
+        self.compartments
+            .get(&pos1)
+            .into_iter()
+            .flatten()
+            .any(|key| key.2 <= distance && other.contains(key))
+    }
+}
+
+/* Candidates which cannot be true together with any of `ends`, given that one of the ends is true */
+pub(crate) fn eliminations(grid: &Grid, peers: &Peers, ends: &[Candidate]) -> Vec<Candidate> {
+    grid.iter_by_indeterminates()
+        .into_iter()
+        .flat_map(|(pos, set)| set.into_iter().map(move |n| (pos, n)))
+        .filter(|&candidate| ends.iter().all(|&end| peers.conflicts(candidate, end)))
+        .collect()
+}
+
+fn other(set: BitSet, n: u8) -> u8 {
+    set.into_iter().find(|&m| m != n).unwrap()
+}
+
+fn bivalue_cells(grid: &Grid) -> Vec<(Point, BitSet)> {
+    grid.iter_by_indeterminates()
+        .into_iter()
+        .filter(|(_, set)| set.len() == 2)
+        .collect()
+}
+
+fn remove_all(grid: &mut Grid, candidates: &[Candidate]) -> Result<(), ValidationResult> {
+    for &(pos, n) in candidates {
+        grid.set_impossible(pos, n)?;
+    }
+    Ok(())
+}
+
+/* Chain of bivalue cells where each cell rules out one number of the next one. If the first cell is not `a`,
+ * the last cell is `z`, so anything conflicting with both can be removed. */
+pub fn xy_chain(grid: &mut Grid) -> StrategyReturn {
+    let peers = Peers::new(grid);
+    let bivalues = bivalue_cells(grid);
+    let sets: HashMap<Point, BitSet> = bivalues.iter().copied().collect();
+
+    for &(start, set) in &bivalues {
+        for a in set {
+            let first = (start, other(set, a));
+            let mut parents: HashMap<Candidate, Candidate> = HashMap::new();
+            let mut queue = VecDeque::from([first]);
+            while let Some(node) = queue.pop_front() {
+                for &(pos, set) in &bivalues {
+                    for n in set {
+                        let next = (pos, other(set, n));
+                        if next == first || parents.contains_key(&next) || !peers.conflicts(node, (pos, n)) {
+                            continue;
+                        }
+                        parents.insert(next, node);
+                        queue.push_back(next);
+
+                        let mut chain = vec![next];
+                        while let Some(&parent) = parents.get(chain.last().unwrap()) {
+                            chain.push(parent);
+                        }
+                        chain.reverse();
+                        if chain.len() < 3 {
+                            continue;
+                        }
+
+                        let removed = eliminations(grid, &peers, &[(start, a), next]);
+                        if removed.is_empty() {
+                            continue;
+                        }
+                        remove_all(grid, &removed)?;
+                        let colors = vec![
+                            chain.iter().map(|&(pos, n)| (pos, other(sets[&pos], n))).collect(),
+                            chain.clone(),
+                        ];
+                        let meta = SolveMetadata {
+                            colors,
+                            ..Default::default()
+                        };
+                        let ty = XyChain(chain.into_iter().map(|(pos, _)| pos).collect(), removed);
+                        return Ok(Some(SolveResults { ty, meta }));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/* A pivot cell with three numbers, each of which either stays or forces a number in one of two bivalue
+ * wings. One of these three is true, so anything conflicting with all of them can be removed. */
+pub fn xyz_wing(grid: &mut Grid) -> StrategyReturn {
+    let peers = &Peers::new(grid);
+    let bivalues = bivalue_cells(grid);
+    let forced = |candidate: Candidate| {
+        bivalues
+            .iter()
+            .filter(move |(pos, _)| *pos != candidate.0)
+            .flat_map(move |&(pos, set)| {
+                set.into_iter()
+                    .filter(move |&n| peers.conflicts(candidate, (pos, n)))
+                    .map(move |n| (pos, other(set, n)))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    for (pivot, set) in grid.iter_by_indeterminates() {
+        if set.len() != 3 {
+            continue;
+        }
+        for stay in set {
+            let [a, b] = set.into_iter().filter(|&n| n != stay).collect::<Vec<_>>()[..] else {
+                unreachable!()
+            };
+            for wing_a in forced((pivot, a)) {
+                for wing_b in forced((pivot, b)) {
+                    if wing_a.0 == wing_b.0 {
+                        continue;
+                    }
+                    let ends = [(pivot, stay), wing_a, wing_b];
+                    let removed = eliminations(grid, peers, &ends);
+                    if removed.is_empty() {
+                        continue;
+                    }
+                    let colors = vec![[pivot, wing_a.0, wing_b.0]
+                        .into_iter()
+                        .flat_map(|pos| grid.get_cell(pos).to_possibles().into_iter().map(move |n| (pos, n)))
+                        .collect()];
+                    remove_all(grid, &removed)?;
+                    let meta = SolveMetadata {
+                        colors,
+                        ..Default::default()
+                    };
+                    return Ok(Some(SolveResults {
+                        ty: XyzWing(pivot, removed),
+                        meta,
+                    }));
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solve_result::ValidationError::OutOfStrats;
+    use crate::solver::solve_basic;
+    use crate::strats::y_wing;
+    use crate::utils::*;
+
+    #[test]
+    fn test_xy_chain() {
+        let mut grid = g("
+.....
+.....
+..##.
+..#..
+.....
+");
+        grid.cells[0][0] = det([1, 2]);
+        grid.cells[0][4] = det([2, 3]);
+        grid.cells[4][4] = det([3, 4]);
+        grid.cells[4][1] = det([4, 1]);
+
+        assert_eq!(solve_basic(&mut grid), Err(OutOfStrats));
+        assert_eq!(y_wing(&mut grid), Ok(None));
+
+        /* either (0, 0) or (1, 4) is 1 */
+        assert_eq!(
+            xy_chain(&mut grid),
+            Ok(Some(SolveResults {
+                ty: XyChain(vec![(0, 0), (4, 0), (4, 4), (1, 4)], vec![((1, 0), 1), ((0, 4), 1)]),
+                meta: SolveMetadata {
+                    colors: vec![
+                        vec![((0, 0), 1), ((4, 0), 2), ((4, 4), 3), ((1, 4), 4)],
+                        vec![((0, 0), 2), ((4, 0), 3), ((4, 4), 4), ((1, 4), 1)]
+                    ],
+                    ..Default::default()
+                }
+            }))
+        );
+        assert_eq!(grid.cells[0][1], det([2, 3, 4, 5]));
+        assert_eq!(grid.cells[4][0], det([2, 3, 4, 5]));
+    }
+
+    #[test]
+    fn test_xyz_wing() {
+        let mut grid = g("
+.......
+.......
+.......
+..#.#..
+.......
+.......
+.......
+");
+        grid.cells[3][1] = det([1, 2, 5]);
+        grid.cells[3][3] = det([1, 3]);
+        grid.cells[3][5] = det([2, 3]);
+        assert_eq!(solve_basic(&mut grid), Err(OutOfStrats));
+        assert_eq!(grid.cells[3][0], det([1, 2, 3, 4, 6]));
+
+        /* 1 and 2 in the pivot make a wing 3, and 5 leaves only 4 for its neighbour in the compartment */
+        assert_eq!(
+            xyz_wing(&mut grid),
+            Ok(Some(SolveResults {
+                ty: XyzWing((1, 3), vec![((0, 3), 3)]),
+                meta: SolveMetadata {
+                    colors: vec![vec![
+                        ((1, 3), 1),
+                        ((1, 3), 2),
+                        ((1, 3), 5),
+                        ((3, 3), 1),
+                        ((3, 3), 3),
+                        ((5, 3), 2),
+                        ((5, 3), 3)
+                    ]],
+                    ..Default::default()
+                }
+            }))
+        );
+        assert_eq!(grid.cells[3][0], det([1, 2, 4, 6]));
+    }
+}
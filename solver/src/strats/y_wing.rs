@@ -44,7 +44,10 @@ pub fn y_wing(grid: &mut Grid) -> Result<Option<SolveResults>, ValidationResult>
                         .into_iter()
                         .flat_map(|num| vec![((x, y), num), (pos1, num), (pos2, num)])
                         .collect()];
-                    let meta = SolveMetadata { colors };
+                    let meta = SolveMetadata {
+                        colors,
+                        ..Default::default()
+                    };
                     let ty = YWing(pos, num);
                     return Ok(Some(SolveResults { meta, ty }));
                 }
@@ -95,7 +98,8 @@ mod tests {
                         ((1, 1), 3),
                         ((3, 1), 3),
                         ((1, 3), 3)
-                    ]]
+                    ]],
+                    ..Default::default()
                 }
             })),
             y_wing(&mut grid)
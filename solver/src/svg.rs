@@ -0,0 +1,352 @@
+//! SVG rendering of grids, for printing puzzles and illustrating solve steps
+
+use crate::bitset::BitSet;
+use crate::grid::Cell::*;
+use crate::grid::{Grid, Point};
+use crate::solve_result::{highlight, SolveResults};
+use std::fmt::Write;
+
+pub const HIGHLIGHT_COLORS: [&str; 4] = ["#4caf50", "#e040fb", "#29b6f6", "#ffb300"];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SvgOptions {
+    pub cell_size: usize,
+    /* Draw the candidates of unsolved cells */
+    pub candidates: bool,
+    /* Draw solved cells; without this only the givens are shown */
+    pub solutions: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions {
+            cell_size: 40,
+            candidates: true,
+            solutions: true,
+        }
+    }
+}
+
+impl SvgOptions {
+    pub fn puzzle() -> SvgOptions {
+        SvgOptions {
+            candidates: false,
+            solutions: false,
+            ..SvgOptions::default()
+        }
+    }
+}
+
+/* Width and height of the rendered grid in pixels, including the outer border */
+pub fn grid_size(grid: &Grid, options: &SvgOptions) -> (usize, usize) {
+    (grid.x * options.cell_size + 4, grid.y * options.cell_size + 4)
+}
+
+/* Render the grid as a <g> element with its top left corner at (left, top) */
+pub fn grid_group(grid: &Grid, colors: &[Vec<(Point, u8)>], options: &SvgOptions, left: usize, top: usize) -> String {
+    render_group(grid, None, colors, options, left, top)
+}
+
+/* Like grid_group, but candidates removed since the previous grid are drawn crossed out in red */
+pub fn step_group(
+    previous: &Grid,
+    grid: &Grid,
+    colors: &[Vec<(Point, u8)>],
+    options: &SvgOptions,
+    left: usize,
+    top: usize,
+) -> String {
+    render_group(grid, Some(previous), colors, options, left, top)
+}
+
+fn render_group(
+    grid: &Grid,
+    previous: Option<&Grid>,
+    colors: &[Vec<(Point, u8)>],
+    options: &SvgOptions,
+    left: usize,
+    top: usize,
+) -> String {
+    let size = options.cell_size;
+    let (width, height) = grid_size(grid, options);
+    let font = "font-family=\"sans-serif\" text-anchor=\"middle\" dominant-baseline=\"central\"";
+    let mut out = String::new();
+
+    writeln!(out, "<g transform=\"translate({} {})\">", left, top).unwrap();
+    writeln!(
+        out,
+        "<rect x=\"1\" y=\"1\" width=\"{}\" height=\"{}\" fill=\"white\" stroke=\"black\" stroke-width=\"2\"/>",
+        width - 2,
+        height - 2
+    )
+    .unwrap();
+
+    for ((x, y), cell) in grid.iter_by_cells() {
+        let cx = 2 + x * size;
+        let cy = 2 + y * size;
+        let center = (cx + size / 2, cy + size / 2);
+        match cell {
+            Black | Blocker(_) => {
+                writeln!(
+                    out,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"black\"/>",
+                    cx, cy, size, size
+                )
+                .unwrap();
+            }
+            _ => {
+                writeln!(
+                    out,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"#888\" stroke-width=\"1\"/>",
+                    cx, cy, size, size
+                )
+                .unwrap();
+            }
+        }
+
+        match cell {
+            Blocker(n) => writeln!(
+                out,
+                "<text x=\"{}\" y=\"{}\" font-size=\"{}\" fill=\"white\" font-weight=\"bold\" {}>{}</text>",
+                center.0,
+                center.1,
+                size * 3 / 5,
+                font,
+                n
+            )
+            .unwrap(),
+            Requirement(n) => writeln!(
+                out,
+                "<text x=\"{}\" y=\"{}\" font-size=\"{}\" fill=\"black\" font-weight=\"bold\" {}>{}</text>",
+                center.0,
+                center.1,
+                size * 3 / 5,
+                font,
+                n
+            )
+            .unwrap(),
+            Solution(n) if options.solutions => {
+                if let Some(color) = highlight(colors, &HIGHLIGHT_COLORS, (x, y), n) {
+                    writeln!(
+                        out,
+                        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" fill-opacity=\"0.4\"/>",
+                        cx, cy, size, size, color
+                    )
+                    .unwrap();
+                }
+                writeln!(
+                    out,
+                    "<text x=\"{}\" y=\"{}\" font-size=\"{}\" fill=\"#1a56c4\" {}>{}</text>",
+                    center.0,
+                    center.1,
+                    size * 3 / 5,
+                    font,
+                    n
+                )
+                .unwrap()
+            }
+            Indeterminate(set) if options.candidates => {
+                let sub = size / 3;
+                let removed = match previous.map(|previous| previous.get_cell((x, y))) {
+                    Some(Indeterminate(before)) => before.difference(set),
+                    _ => BitSet::new(),
+                };
+                for num in set.union(removed) {
+                    let i = (num - 1) as usize;
+                    let (sx, sy) = (cx + (i % 3) * sub + sub / 2, cy + (i / 3) * sub + sub / 2);
+                    if let Some(color) = highlight(colors, &HIGHLIGHT_COLORS, (x, y), num) {
+                        writeln!(
+                            out,
+                            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\" fill-opacity=\"0.6\"/>",
+                            sx,
+                            sy,
+                            sub / 2,
+                            color
+                        )
+                        .unwrap();
+                    }
+                    writeln!(
+                        out,
+                        "<text x=\"{}\" y=\"{}\" font-size=\"{}\" fill=\"{}\" {}>{}</text>",
+                        sx,
+                        sy,
+                        sub * 4 / 5,
+                        if removed.contains(num) { "#d32f2f" } else { "#555" },
+                        font,
+                        num
+                    )
+                    .unwrap();
+                    if removed.contains(num) {
+                        let r = sub * 2 / 5;
+                        writeln!(
+                            out,
+                            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#d32f2f\" stroke-width=\"1.5\"/>",
+                            sx - r,
+                            sy + r,
+                            sx + r,
+                            sy - r
+                        )
+                        .unwrap();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    writeln!(out, "</g>").unwrap();
+    out
+}
+
+pub fn svg_document(width: usize, height: usize, body: &str) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n{}</svg>\n",
+        width, height, width, height, body
+    )
+}
+
+pub fn grid_to_svg(grid: &Grid, colors: &[Vec<(Point, u8)>], options: &SvgOptions) -> String {
+    let (width, height) = grid_size(grid, options);
+    svg_document(width, height, &grid_group(grid, colors, options, 0, 0))
+}
+
+/* The puzzle as printed for solving: givens and black cells only */
+pub fn puzzle_to_svg(grid: &Grid) -> String {
+    grid_to_svg(grid, &[], &SvgOptions::puzzle())
+}
+
+/* A solve step drawn on the grid it was applied to, highlighting the candidates it used */
+pub fn step_to_svg(grid: &Grid, step: &SolveResults, options: &SvgOptions) -> String {
+    grid_to_svg(grid, &step.meta.colors, options)
+}
+
+/* A solve step drawn on the grid it produced, showing the candidates it removed from the previous grid */
+pub fn step_result_to_svg(previous: &Grid, grid: &Grid, step: &SolveResults, options: &SvgOptions) -> String {
+    let (width, height) = grid_size(grid, options);
+    svg_document(width, height, &step_group(previous, grid, &step.meta.colors, options, 0, 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Cell;
+    use crate::solve_result::{SolveMetadata, SolveType};
+    use crate::utils::*;
+
+    #[test]
+    fn test_puzzle() {
+        let mut grid = g("
+#1.
+.b.
+..#
+");
+        grid.set_cell((2, 0), Cell::Solution(2));
+        let svg = puzzle_to_svg(&grid);
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"124\" height=\"124\""));
+        assert_eq!(svg.matches("fill=\"black\"/>").count(), 3);
+        assert!(svg.contains("fill=\"white\" font-weight=\"bold\" font-family=\"sans-serif\" text-anchor=\"middle\" dominant-baseline=\"central\">2</text>"));
+        assert!(svg.contains(">1</text>"));
+        assert!(!svg.contains("#1a56c4"));
+        assert!(!svg.contains("<circle"));
+    }
+
+    #[test]
+    fn test_step() {
+        let mut grid = g("
+..
+..
+");
+        grid.set_cell((0, 0), Cell::Solution(1));
+        grid.set_cell((1, 0), det([1, 2]));
+        let step = SolveResults {
+            ty: SolveType::Medusa,
+            meta: SolveMetadata {
+                colors: vec![vec![((0, 0), 1)], vec![((1, 0), 2)]],
+                ..Default::default()
+            },
+        };
+        let svg = step_to_svg(&grid, &step, &SvgOptions::default());
+
+        assert_eq!(svg.matches("<circle").count(), 1);
+        assert!(svg.contains("fill=\"#e040fb\" fill-opacity=\"0.6\"/>"));
+        assert!(svg.contains("fill=\"#4caf50\" fill-opacity=\"0.4\"/>"));
+        assert!(svg.contains(
+            "fill=\"#1a56c4\" font-family=\"sans-serif\" text-anchor=\"middle\" dominant-baseline=\"central\">1</text>"
+        ));
+        assert!(!svg.contains("<line"));
+
+        let mut after = grid.clone();
+        after.set_cell((1, 0), det([2]));
+        let svg = step_result_to_svg(&grid, &after, &step, &SvgOptions::default());
+        assert_eq!(svg.matches("<line").count(), 1);
+        assert_eq!(svg.matches("fill=\"#d32f2f\"").count(), 1);
+    }
+
+    #[test]
+    fn test_sizes() {
+        let grid = g("
+12#.7...6
+.#.4..3..
+.#.#i....
+7..3.....
+9#3#.#8#7
+.....1..4
+....h#.#.
+..9..8.#.
+5...4.#32
+");
+        let svg = grid_to_svg(&grid, &[], &SvgOptions::default());
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"364\" height=\"364\""));
+        assert_eq!(svg.matches("stroke=\"#888\"").count() + svg.matches("fill=\"black\"/>").count(), 81);
+        assert_eq!(svg.matches("fill=\"#555\"").count(), 47 * 9);
+
+        let options = SvgOptions {
+            cell_size: 30,
+            ..SvgOptions::puzzle()
+        };
+        assert_eq!(grid_size(&grid, &options), (274, 274));
+        assert!(grid_to_svg(&grid, &[], &options).contains("<rect x=\"242\" y=\"242\" width=\"30\" height=\"30\""));
+
+        let grid = g("
+1.#4
+..#.
+#...
+4..#
+");
+        assert_eq!(grid_size(&grid, &SvgOptions::default()), (164, 164));
+        assert!(puzzle_to_svg(&grid).contains("width=\"164\" height=\"164\""));
+    }
+
+    #[test]
+    fn test_palette_wraps() {
+        let grid = g("
+...
+...
+...
+");
+        let colors = (0..5).map(|i| vec![((i % 3, i / 3), 1)]).collect::<Vec<_>>();
+        let svg = grid_to_svg(&grid, &colors, &SvgOptions::default());
+
+        assert_eq!(svg.matches("<circle").count(), 5);
+        assert_eq!(svg.matches("fill=\"#4caf50\"").count(), 2);
+        for color in &HIGHLIGHT_COLORS[1..] {
+            assert_eq!(svg.matches(&format!("fill=\"{}\"", color)).count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_hidden_cells_are_not_highlighted() {
+        let mut grid = g("
+..
+..
+");
+        grid.set_cell((0, 0), Cell::Solution(1));
+        let colors = vec![vec![((0, 0), 1), ((1, 0), 2)]];
+        let svg = grid_to_svg(&grid, &colors, &SvgOptions::puzzle());
+
+        assert!(!svg.contains("<circle"));
+        assert!(!svg.contains("#4caf50"));
+        assert!(!svg.contains(">1</text>"));
+    }
+}
@@ -0,0 +1,573 @@
+//! Transformations which turn a puzzle into an equivalent one with the same difficulty
+//!
+//! Besides the grid itself, a transform can be applied to a solve history, mapping every position, digit and
+//! line referenced by the steps so that the history describes solving the transformed grid.
+
+use crate::bitset::BitSet;
+use crate::grid::Cell::*;
+use crate::grid::{Cell, Grid, Point};
+use crate::solve_result::SolveType::*;
+use crate::solve_result::ValidationError::*;
+use crate::solve_result::{SolveMetadata, SolveResults, SolveType, ValidationResult};
+use crate::strats::UrResult;
+use std::rc::Rc;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Transform {
+    Transpose,
+    /* Clockwise quarter turns */
+    Rotate(usize),
+    /* Swap left and right */
+    MirrorHorizontal,
+    /* Swap top and bottom */
+    MirrorVertical,
+    /* n -> size + 1 - n */
+    ReverseDigits,
+    /* The old rows in their new order, so that new row i is old row order[i] */
+    PermuteRows(Vec<usize>),
+    /* The old columns in their new order */
+    PermuteCols(Vec<usize>),
+}
+
+fn position(order: &[usize], old: usize) -> usize {
+    order.iter().position(|&o| o == old).unwrap()
+}
+
+fn is_permutation(order: &[usize], size: usize) -> bool {
+    let mut sorted = order.to_vec();
+    sorted.sort_unstable();
+    sorted == (0..size).collect::<Vec<_>>()
+}
+
+/* Compartments of every line as sets of indices along the line */
+fn compartment_indices(grid: &Grid, vertical: bool) -> Vec<Vec<Vec<usize>>> {
+    let lines = if vertical {
+        grid.iter_by_col_compartments()
+    } else {
+        grid.iter_by_row_compartments()
+    };
+    lines
+        .into_iter()
+        .map(|line| {
+            line.into_iter()
+                .map(|compartment| {
+                    compartment
+                        .cells
+                        .iter()
+                        .map(|((x, y), _)| if vertical { *y } else { *x })
+                        .collect()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+impl Transform {
+    pub fn map_point(&self, (x, y): Point, size: usize) -> Point {
+        let last = size - 1;
+        match self {
+            Transform::Transpose => (y, x),
+            Transform::Rotate(turns) => match turns % 4 {
+                0 => (x, y),
+                1 => (last - y, x),
+                2 => (last - x, last - y),
+                _ => (y, last - x),
+            },
+            Transform::MirrorHorizontal => (last - x, y),
+            Transform::MirrorVertical => (x, last - y),
+            Transform::ReverseDigits => (x, y),
+            Transform::PermuteRows(order) => (x, position(order, y)),
+            Transform::PermuteCols(order) => (position(order, x), y),
+        }
+    }
+
+    pub fn map_digit(&self, n: u8, size: usize) -> u8 {
+        match self {
+            Transform::ReverseDigits => size as u8 + 1 - n,
+            _ => n,
+        }
+    }
+
+    fn map_digits(&self, set: BitSet, size: usize) -> BitSet {
+        set.into_iter().map(|n| self.map_digit(n, size)).collect()
+    }
+
+    /* Where a row (or a column if vertical) ends up, as (vertical, index) */
+    pub fn map_line(&self, vertical: bool, index: usize, size: usize) -> (bool, usize) {
+        let (a, b) = if vertical {
+            ((index, 0), (index, 1))
+        } else {
+            ((0, index), (1, index))
+        };
+        let (a, b) = (self.map_point(a, size), self.map_point(b, size));
+        if a.0 == b.0 {
+            (true, a.0)
+        } else {
+            (false, a.1)
+        }
+    }
+
+    fn swaps_axes(&self, size: usize) -> bool {
+        size > 1 && self.map_line(false, 0, size).0
+    }
+
+    fn map_cell(&self, cell: &Cell, size: usize) -> Cell {
+        match cell {
+            Requirement(n) => Requirement(self.map_digit(*n, size)),
+            Solution(n) => Solution(self.map_digit(*n, size)),
+            Blocker(n) => Blocker(self.map_digit(*n, size)),
+            Indeterminate(set) => Indeterminate(self.map_digits(*set, size)),
+            Black => Black,
+        }
+    }
+
+    /* Row and column permutations are only allowed if every compartment stays in one piece */
+    pub fn check(&self, grid: &Grid) -> Result<(), String> {
+        let (vertical, order) = match self {
+            Transform::PermuteRows(order) => (true, order),
+            Transform::PermuteCols(order) => (false, order),
+            _ => return Ok(()),
+        };
+        let size = if vertical { grid.y } else { grid.x };
+        if !is_permutation(order, size) {
+            return Err(format!("{:?} is not a permutation of 0..{}", order, size));
+        }
+
+        let mut before = compartment_indices(grid, vertical);
+        let mut after = compartment_indices(&self.map_grid(grid), vertical);
+        for line in after.iter_mut() {
+            for compartment in line.iter_mut() {
+                compartment.iter_mut().for_each(|i| *i = order[*i]);
+                compartment.sort_unstable();
+            }
+        }
+        for line in before.iter_mut().chain(after.iter_mut()) {
+            line.sort();
+        }
+        if before != after {
+            return Err(format!(
+                "Permuting the {} breaks up compartments",
+                if vertical { "rows" } else { "columns" }
+            ));
+        }
+        Ok(())
+    }
+
+    fn map_grid(&self, grid: &Grid) -> Grid {
+        let size = grid.x;
+        let mut res = grid.clone();
+        for ((x, y), cell) in grid.iter_by_cells() {
+            let (nx, ny) = self.map_point((x, y), size);
+            res.cells[ny][nx] = self.map_cell(&cell, size);
+        }
+        for vertical in [false, true] {
+            for index in 0..size {
+                let (new_vertical, new_index) = self.map_line(vertical, index, size);
+                *res.requirements_mut(new_vertical, (new_index, new_index)) =
+                    self.map_digits(grid.requirements(vertical, (index, index)), size);
+                *res.forbidden_mut(new_vertical, (new_index, new_index)) =
+                    self.map_digits(grid.forbidden(vertical, (index, index)), size);
+            }
+        }
+        res
+    }
+
+    pub fn apply(&self, grid: &Grid) -> Result<Grid, String> {
+        self.check(grid)?;
+        Ok(self.map_grid(grid))
+    }
+
+    fn map_points(&self, points: &[Point], size: usize) -> Vec<Point> {
+        points.iter().map(|&pos| self.map_point(pos, size)).collect()
+    }
+
+    fn map_meta(&self, meta: &SolveMetadata, size: usize) -> SolveMetadata {
+        SolveMetadata {
+            colors: meta
+                .colors
+                .iter()
+                .map(|group| {
+                    group
+                        .iter()
+                        .map(|&(pos, n)| (self.map_point(pos, size), self.map_digit(n, size)))
+                        .collect()
+                })
+                .collect(),
+            lines: meta
+                .lines
+                .iter()
+                .map(|group| {
+                    group
+                        .iter()
+                        .map(|&(vertical, index)| self.map_line(vertical, index, size))
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    fn map_ur(&self, res: &UrResult, size: usize) -> UrResult {
+        let p = |pos: &Point| self.map_point(*pos, size);
+        let d = |n: &u8| self.map_digit(*n, size);
+        match res {
+            UrResult::SingleUnique(pos, n) => UrResult::SingleUnique(p(pos), d(n)),
+            UrResult::IntraCompartmentUnique(pos, n) => UrResult::IntraCompartmentUnique(p(pos), d(n)),
+            UrResult::ClosedSetCompartment(list, n) => {
+                UrResult::ClosedSetCompartment(self.map_points(list, size), d(n))
+            }
+            UrResult::SingleCellWouldBecomeFree(pos, n) => UrResult::SingleCellWouldBecomeFree(p(pos), d(n)),
+            UrResult::UrSetti(list, vertical, n) => {
+                UrResult::UrSetti(self.map_points(list, size), *vertical != self.swaps_axes(size), d(n))
+            }
+            UrResult::SolutionCausesClosedSets(pos, n) => UrResult::SolutionCausesClosedSets(p(pos), d(n)),
+        }
+    }
+
+    fn map_solve_type(&self, ty: &SolveType, grid: &Grid) -> SolveType {
+        let size = grid.x;
+        let p = |pos: &Point| self.map_point(*pos, size);
+        let d = |n: &u8| self.map_digit(*n, size);
+        match ty {
+            HiddenSets(list, set) => HiddenSets(list.iter().map(p).collect(), self.map_digits(*set, size)),
+            Setti(set) => Setti(self.map_digits(*set, size)),
+            YWing(pos, n) => YWing(p(pos), d(n)),
+            XyzWing(pos, removed) => XyzWing(p(pos), removed.iter().map(|(pos, n)| (p(pos), d(n))).collect()),
+            XChain(n, list) => XChain(d(n), list.iter().map(p).collect()),
+            FinnedFish(size, sashimi, n, list) => FinnedFish(*size, *sashimi, d(n), list.iter().map(p).collect()),
+            XyChain(list, removed) => {
+                XyChain(list.iter().map(p).collect(), removed.iter().map(|(pos, n)| (p(pos), d(n))).collect())
+            }
+            Aic(chain, removed) => Aic(
+                chain.iter().map(|(pos, n)| (p(pos), d(n))).collect(),
+                removed.iter().map(|(pos, n)| (p(pos), d(n))).collect(),
+            ),
+            UniqueRequirement(res) => UniqueRequirement(self.map_ur(res, size)),
+            PatternOverlay(n, removed, placed) => {
+                PatternOverlay(d(n), removed.iter().map(p).collect(), placed.iter().map(p).collect())
+            }
+            ForcingChain(branches, removed, placed) => ForcingChain(
+                branches.iter().map(|(pos, n)| (p(pos), d(n))).collect(),
+                removed.iter().map(|(pos, n)| (p(pos), d(n))).collect(),
+                placed.iter().map(|(pos, n)| (p(pos), d(n))).collect(),
+            ),
+            StartGuess(pos, n) => StartGuess(p(pos), d(n)),
+            GuessStep(pos, n, steps, result) => {
+                GuessStep(p(pos), d(n), Rc::new(self.map_history(steps)), self.map_grid(result))
+            }
+            EndGuess(e) => EndGuess(self.map_validation_result(e, &self.map_grid(grid))),
+            other => other.clone(),
+        }
+    }
+
+    /* `grid` is the grid after the step in the original orientation */
+    pub fn map_solve_results(&self, res: &SolveResults, grid: &Grid) -> SolveResults {
+        SolveResults {
+            ty: self.map_solve_type(&res.ty, grid),
+            meta: self.map_meta(&res.meta, grid.x),
+        }
+    }
+
+    /* `grid` is the grid the error was found in, already transformed */
+    pub fn map_validation_result(&self, res: &ValidationResult, grid: &Grid) -> ValidationResult {
+        let size = grid.x;
+        let p = |pos: &Point| self.map_point(*pos, size);
+        let d = |n: &u8| self.map_digit(*n, size);
+        /* the first cell of the compartment moves when mirroring or rotating */
+        let top_left = |vertical: bool, pos: &Point| {
+            let vertical = vertical != self.swaps_axes(size);
+            let compartment = if vertical {
+                grid.vertical_compartment_containing(p(pos))
+            } else {
+                grid.horizontal_compartment_containing(p(pos))
+            };
+            (vertical, compartment.cells[0].0)
+        };
+        let sorted = |a: u8, b: u8| (a.min(b), a.max(b));
+        let ty = match &res.ty {
+            EmptyCell { pos } => EmptyCell { pos: p(pos) },
+            Conflict { pos1, pos2, val } => Conflict {
+                pos1: p(pos1),
+                pos2: p(pos2),
+                val: d(val),
+            },
+            Sequence {
+                vertical,
+                top_left: pos,
+                range: (min, max),
+                missing,
+            } => {
+                let (vertical, top_left) = top_left(*vertical, pos);
+                Sequence {
+                    vertical,
+                    top_left,
+                    range: sorted(d(min), d(max)),
+                    missing: d(missing),
+                }
+            }
+            SequenceTooLarge {
+                vertical,
+                top_left: pos,
+                contains: (a, b),
+                max_ranges: ((min_a, max_a), (min_b, max_b)),
+            } => {
+                let (vertical, top_left) = top_left(*vertical, pos);
+                let (range_a, range_b) = (sorted(d(min_a), d(max_a)), sorted(d(min_b), d(max_b)));
+                SequenceTooLarge {
+                    vertical,
+                    top_left,
+                    contains: sorted(d(a), d(b)),
+                    max_ranges: (range_a.min(range_b), range_a.max(range_b)),
+                }
+            }
+            RequirementBlockerConflict {
+                vertical,
+                index,
+                number,
+            } => {
+                let (vertical, index) = self.map_line(*vertical, *index, size);
+                RequirementBlockerConflict {
+                    vertical,
+                    index,
+                    number: d(number),
+                }
+            }
+            RequiredNumberMissing {
+                vertical,
+                index,
+                number,
+            } => {
+                let (vertical, index) = self.map_line(*vertical, *index, size);
+                RequiredNumberMissing {
+                    vertical,
+                    index,
+                    number: d(number),
+                }
+            }
+            BlockedNumberPresent {
+                vertical,
+                index,
+                number,
+            } => {
+                let (vertical, index) = self.map_line(*vertical, *index, size);
+                BlockedNumberPresent {
+                    vertical,
+                    index,
+                    number: d(number),
+                }
+            }
+            Ambiguous { cells, solutions } => Ambiguous {
+                cells: self.map_points(cells, size),
+                solutions: solutions
+                    .iter()
+                    .map(|solution| solution.iter().map(d).collect())
+                    .collect(),
+            },
+            other @ (NoSolutions | OutOfStrats) => other.clone(),
+        };
+        ValidationResult {
+            ty,
+            meta: self.map_meta(&res.meta, size),
+        }
+    }
+
+    /* A history of (grid after the step, step), as stored in GuessStep */
+    pub fn map_history(&self, history: &[(Grid, SolveResults)]) -> Vec<(Grid, SolveResults)> {
+        history
+            .iter()
+            .map(|(grid, res)| (self.map_grid(grid), self.map_solve_results(res, grid)))
+            .collect()
+    }
+
+    /* Transform a puzzle together with the history of solving it */
+    pub fn apply_with_history(
+        &self,
+        grid: &Grid,
+        history: &[(Grid, SolveResults)],
+    ) -> Result<(Grid, Vec<(Grid, SolveResults)>), String> {
+        self.check(grid)?;
+        Ok((self.map_grid(grid), self.map_history(history)))
+    }
+}
+
+impl Grid {
+    pub fn transform(&self, transform: &Transform) -> Result<Grid, String> {
+        transform.apply(self)
+    }
+
+    pub fn transpose(&self) -> Grid {
+        Transform::Transpose.map_grid(self)
+    }
+
+    pub fn rotate(&self, clockwise_turns: usize) -> Grid {
+        Transform::Rotate(clockwise_turns).map_grid(self)
+    }
+
+    pub fn mirror_horizontal(&self) -> Grid {
+        Transform::MirrorHorizontal.map_grid(self)
+    }
+
+    pub fn mirror_vertical(&self) -> Grid {
+        Transform::MirrorVertical.map_grid(self)
+    }
+
+    pub fn reverse_digits(&self) -> Grid {
+        Transform::ReverseDigits.map_grid(self)
+    }
+
+    pub fn permute_rows(&self, order: &[usize]) -> Result<Grid, String> {
+        Transform::PermuteRows(order.to_vec()).apply(self)
+    }
+
+    pub fn permute_cols(&self, order: &[usize]) -> Result<Grid, String> {
+        Transform::PermuteCols(order.to_vec()).apply(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::solve_round;
+    use crate::utils::*;
+
+    const PUZZLE: &str = "
+12#.7...6
+.#.4..3..
+.#.#i....
+7..3.....
+9#3#.#8#7
+.....1..4
+....h#.#.
+..9..8.#.
+5...4.#32
+";
+
+    fn history(grid: &Grid) -> (Grid, Vec<(Grid, SolveResults)>) {
+        let mut grid = grid.clone();
+        let mut history = Vec::new();
+        loop {
+            let step = solve_round(&mut grid, true).unwrap();
+            if step.ty == PuzzleSolved {
+                return (grid, history);
+            }
+            history.push((grid.clone(), step));
+        }
+    }
+
+    #[test]
+    fn test_geometry() {
+        let grid = g(PUZZLE);
+        assert_eq!(grid.rotate(1).get_cell((8, 0)), &Requirement(1));
+        assert_eq!(grid.rotate(2).get_cell((8, 8)), &Requirement(1));
+        assert_eq!(grid.rotate(3).get_cell((0, 8)), &Requirement(1));
+        assert_eq!(grid.rotate(4), grid);
+        assert_eq!(grid.transpose().get_cell((0, 1)), &Requirement(2));
+        assert_eq!(grid.mirror_horizontal().get_cell((8, 0)), &Requirement(1));
+        assert_eq!(grid.mirror_vertical().get_cell((0, 8)), &Requirement(1));
+        assert_eq!(grid.reverse_digits().get_cell((0, 0)), &Requirement(9));
+        assert_eq!(grid.reverse_digits().get_cell((4, 2)), &Blocker(1));
+        assert_eq!(grid.rotate(1), grid.transpose().mirror_horizontal());
+
+        assert_eq!(Transform::Rotate(1).map_line(false, 2, 9), (true, 6));
+        assert_eq!(Transform::MirrorVertical.map_line(false, 2, 9), (false, 6));
+        assert_eq!(Transform::ReverseDigits.map_line(true, 2, 9), (true, 2));
+    }
+
+    #[test]
+    fn test_permutations() {
+        let grid = g("
+...1
+.#..
+....
+....
+");
+        assert!(grid.permute_rows(&[0, 1, 2]).is_err());
+        assert!(grid.permute_rows(&[0, 1, 1, 2]).is_err());
+
+        /* the last two rows and columns always share their compartments */
+        let swapped = grid.permute_cols(&[0, 1, 3, 2]).unwrap();
+        assert_eq!(swapped.get_cell((2, 0)), &Requirement(1));
+        assert_eq!(grid.permute_rows(&[0, 1, 3, 2]).unwrap().cells[2], grid.cells[3]);
+
+        /* moving the black cell to the edge would merge the compartments around it */
+        assert!(grid.permute_cols(&[1, 0, 2, 3]).is_err());
+        assert!(grid.permute_rows(&[1, 0, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_history() {
+        let grid = g(PUZZLE);
+        let (solution, steps) = history(&grid);
+
+        for transform in [
+            Transform::Transpose,
+            Transform::Rotate(1),
+            Transform::Rotate(2),
+            Transform::MirrorHorizontal,
+            Transform::ReverseDigits,
+        ] {
+            let (transformed, mapped) = transform.apply_with_history(&grid, &steps).unwrap();
+            let (transformed_solution, _) = history(&transformed);
+            assert_eq!(mapped.len(), steps.len());
+            assert_eq!(mapped.last().unwrap().0, transformed_solution);
+            assert_eq!(transform.apply(&solution).unwrap(), transformed_solution);
+            for ((_, before), (_, after)) in steps.iter().zip(&mapped) {
+                assert_eq!(std::mem::discriminant(&before.ty), std::mem::discriminant(&after.ty));
+                assert_eq!(before.meta.colors.len(), after.meta.colors.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_validation_result() {
+        let grid = g("
+.....
+.#...
+.....
+.....
+.....
+");
+        let err = ValidationResult::from(Sequence {
+            vertical: false,
+            top_left: (2, 1),
+            range: (1, 3),
+            missing: 2,
+        });
+        let mirrored = Transform::MirrorHorizontal.map_grid(&grid);
+        let mapped = Transform::MirrorHorizontal.map_validation_result(&err, &mirrored);
+        assert_eq!(
+            mapped.ty,
+            Sequence {
+                vertical: false,
+                top_left: (0, 1),
+                range: (1, 3),
+                missing: 2
+            }
+        );
+
+        let rotated = Transform::Rotate(1).map_grid(&grid);
+        let mapped = Transform::ReverseDigits
+            .map_validation_result(&Transform::Rotate(1).map_validation_result(&err, &rotated), &rotated);
+        assert_eq!(
+            mapped.ty,
+            Sequence {
+                vertical: true,
+                top_left: (3, 2),
+                range: (3, 5),
+                missing: 4
+            }
+        );
+
+        let err = ValidationResult::from(Ambiguous {
+            cells: vec![(0, 0), (1, 0)],
+            solutions: vec![vec![1, 2], vec![2, 1]],
+        });
+        let mapped = Transform::ReverseDigits.map_validation_result(&err, &grid);
+        assert_eq!(
+            mapped.ty,
+            Ambiguous {
+                cells: vec![(0, 0), (1, 0)],
+                solutions: vec![vec![5, 4], vec![4, 5]]
+            }
+        );
+    }
+}
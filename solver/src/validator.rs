@@ -23,10 +23,9 @@ pub fn grid_has_conflicts(grid: &Grid) -> Result<(), ValidationResult> {
         for ((x, y), cell) in row {
             if let Some(val) = cell.to_determinate() {
                 if map.contains_key(&val) {
-                    let (other_x, other_y) = map[&val];
                     return Err(Conflict {
-                        pos1: (other_x + 1, other_y + 1),
-                        pos2: (x + 1, y + 1),
+                        pos1: map[&val],
+                        pos2: (x, y),
                         val,
                     }
                     .into());
@@ -198,8 +197,8 @@ pub fn validate(grid: &Grid) -> Result<(), ValidationResult> {
 
 #[cfg(test)]
 mod tests {
-    use crate::solve_result::SolveMetadata;
     use super::*;
+    use crate::solve_result::SolveMetadata;
     use crate::utils::*;
 
     #[test]
@@ -214,8 +213,8 @@ mod tests {
             validate(&grid),
             Err(ValidationResult {
                 ty: Conflict {
-                    pos1: (2, 2),
-                    pos2: (3, 2),
+                    pos1: (1, 1),
+                    pos2: (2, 1),
                     val: 4
                 },
                 meta: SolveMetadata::default()
@@ -235,8 +234,8 @@ mod tests {
             validate(&grid),
             Err(ValidationResult {
                 ty: Conflict {
-                    pos1: (2, 2),
-                    pos2: (2, 3),
+                    pos1: (1, 1),
+                    pos2: (1, 2),
                     val: 4
                 },
                 meta: SolveMetadata::default()
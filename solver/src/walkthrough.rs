@@ -0,0 +1,296 @@
+//! Solve walkthroughs as Markdown or HTML documents, with the grid drawn after every step
+
+use crate::grid::Grid;
+use crate::messages::MessageOptions;
+use crate::solve_result::SolveType::{GuessStep, PuzzleSolved};
+use crate::solve_result::{SolveResults, ValidationResult};
+use crate::solver::run_strat;
+use crate::strategy::StrategyList;
+use crate::svg::{grid_to_svg, puzzle_to_svg, step_result_to_svg, SvgOptions};
+use std::fmt::Write;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WalkthroughFormat {
+    Markdown,
+    Html,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Walkthrough {
+    pub puzzle: Grid,
+    /* The grid after each step, like in the sub-histories of GuessStep */
+    pub steps: Vec<(Grid, SolveResults)>,
+    /* Set if the solver got stuck or found the puzzle to be invalid */
+    pub error: Option<ValidationResult>,
+}
+
+impl Walkthrough {
+    pub fn new(puzzle: &Grid, strats: &StrategyList) -> Walkthrough {
+        let mut grid = puzzle.clone();
+        let mut steps = Vec::new();
+        let error = loop {
+            match run_strat(&mut grid, strats) {
+                Ok(SolveResults { ty: PuzzleSolved, .. }) => break None,
+                Ok(step) => steps.push((grid.clone(), step)),
+                Err(e) => break Some(e),
+            }
+        };
+        Walkthrough {
+            puzzle: puzzle.clone(),
+            steps,
+            error,
+        }
+    }
+
+    pub fn solution(&self) -> &Grid {
+        self.steps.last().map(|(grid, _)| grid).unwrap_or(&self.puzzle)
+    }
+
+    pub fn render(&self, title: &str, format: WalkthroughFormat, options: MessageOptions) -> String {
+        let mut out = String::new();
+        let writer = Writer { format, options };
+        writer.document_start(&mut out, title);
+        writer.figure(&mut out, &puzzle_to_svg(&self.puzzle));
+        writer.history(&mut out, &self.puzzle, &self.steps, "", 2);
+
+        match &self.error {
+            None => {
+                writer.heading(&mut out, 2, "Solved");
+                writer.figure(&mut out, &grid_to_svg(self.solution(), &[], &SvgOptions::default()));
+            }
+            Some(e) => {
+                writer.heading(&mut out, 2, "Stuck");
+                writer.paragraph(&mut out, &e.message().format(options));
+                writer.figure(&mut out, &grid_to_svg(self.solution(), &e.meta.colors, &SvgOptions::default()));
+            }
+        }
+        writer.document_end(&mut out);
+        out
+    }
+
+    pub fn to_markdown(&self, title: &str) -> String {
+        self.render(title, WalkthroughFormat::Markdown, MessageOptions::default())
+    }
+
+    pub fn to_html(&self, title: &str) -> String {
+        self.render(title, WalkthroughFormat::Html, MessageOptions::default())
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+struct Writer {
+    format: WalkthroughFormat,
+    options: MessageOptions,
+}
+
+impl Writer {
+    fn document_start(&self, out: &mut String, title: &str) {
+        match self.format {
+            WalkthroughFormat::Markdown => writeln!(out, "# {}\n", title).unwrap(),
+            WalkthroughFormat::Html => writeln!(
+                out,
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n<style>\n\
+                 body {{ font-family: sans-serif; max-width: 50em; margin: auto; }}\n\
+                 section.guess {{ margin-left: 1em; padding-left: 1em; border-left: 4px solid #1e40af; }}\n\
+                 </style>\n</head>\n<body>\n<h1>{0}</h1>",
+                escape_html(title)
+            )
+            .unwrap(),
+        }
+    }
+
+    fn document_end(&self, out: &mut String) {
+        if self.format == WalkthroughFormat::Html {
+            writeln!(out, "</body>\n</html>").unwrap();
+        }
+    }
+
+    fn heading(&self, out: &mut String, level: usize, text: &str) {
+        let level = level.min(6);
+        match self.format {
+            WalkthroughFormat::Markdown => writeln!(out, "{} {}\n", "#".repeat(level), text).unwrap(),
+            WalkthroughFormat::Html => writeln!(out, "<h{0}>{1}</h{0}>", level, escape_html(text)).unwrap(),
+        }
+    }
+
+    fn paragraph(&self, out: &mut String, text: &str) {
+        match self.format {
+            WalkthroughFormat::Markdown => writeln!(out, "{}\n", text).unwrap(),
+            WalkthroughFormat::Html => writeln!(out, "<p>{}</p>", escape_html(text)).unwrap(),
+        }
+    }
+
+    /* Markdown passes the SVG through as a raw HTML block, which must not contain blank lines */
+    fn figure(&self, out: &mut String, svg: &str) {
+        match self.format {
+            WalkthroughFormat::Markdown => writeln!(out, "{}", svg).unwrap(),
+            WalkthroughFormat::Html => writeln!(out, "<figure>\n{}</figure>", svg).unwrap(),
+        }
+    }
+
+    fn history(&self, out: &mut String, start: &Grid, steps: &[(Grid, SolveResults)], prefix: &str, level: usize) {
+        let mut previous = start;
+        for (i, (grid, step)) in steps.iter().enumerate() {
+            let number = format!("{}{}", prefix, i + 1);
+            if self.format == WalkthroughFormat::Html {
+                writeln!(out, "<section>").unwrap();
+            }
+            self.heading(out, level, &format!("Step {}", number));
+            self.paragraph(out, &step.message().format(self.options));
+            self.figure(out, &step_result_to_svg(previous, grid, step, &SvgOptions::default()));
+
+            if let GuessStep(_, _, guess_steps, _) = &step.ty {
+                if self.format == WalkthroughFormat::Html {
+                    writeln!(out, "<section class=\"guess\">").unwrap();
+                }
+                self.heading(out, level + 1, &format!("Guess for step {}", number));
+                self.history(out, previous, guess_steps, &format!("{}.", number), level + 2);
+                if self.format == WalkthroughFormat::Html {
+                    writeln!(out, "</section>").unwrap();
+                }
+            }
+
+            if self.format == WalkthroughFormat::Html {
+                writeln!(out, "</section>").unwrap();
+            }
+            previous = grid;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coordinate::Notation;
+    use crate::messages::Locale;
+    use crate::solve_result::ValidationError;
+    use crate::utils::*;
+
+    #[test]
+    fn test_markdown() {
+        let walkthrough = Walkthrough::new(
+            &g("
+12#.7...6
+.#.4..3..
+.#.#i....
+7..3.....
+9#3#.#8#7
+.....1..4
+....h#.#.
+..9..8.#.
+5...4.#32
+"),
+            &StrategyList::all(),
+        );
+        assert_eq!(walkthrough.error, None);
+        assert!(walkthrough.solution().is_solved());
+
+        let md = walkthrough.to_markdown("Example");
+        assert!(md.starts_with("# Example\n\n<svg"));
+        assert_eq!(md.matches("\n## Step ").count(), walkthrough.steps.len());
+        assert!(md.contains(&format!("\n{}\n", walkthrough.steps[0].1)));
+        assert!(md.contains("\n## Solved\n"));
+        assert!(!md.contains("\n\n\n"));
+    }
+
+    #[test]
+    fn test_html_guess() {
+        let walkthrough = Walkthrough::new(
+            &g("
+.#2..
+4.#..
+...5.
+.4#.1
+...#.
+"),
+            &StrategyList::all(),
+        );
+        let is_guess = |(_, step): &&(Grid, SolveResults)| matches!(step.ty, GuessStep(..));
+        let guesses = walkthrough.steps.iter().filter(is_guess).count();
+        let first = walkthrough.steps.iter().position(|step| is_guess(&step)).unwrap();
+
+        let html = walkthrough.to_html("Guess <example>");
+        assert!(html.contains("<title>Guess &lt;example&gt;</title>"));
+        assert_eq!(html.matches("<section class=\"guess\">").count(), guesses);
+        assert!(html.contains(&format!(
+            "<h3>Guess for step {}</h3>\n<section>\n<h4>Step {}.1</h4>",
+            first + 1,
+            first + 1
+        )));
+        assert_eq!(html.matches("<section").count(), html.matches("</section>").count());
+        assert!(html.trim_end().ends_with("</html>"));
+
+        let options = MessageOptions {
+            locale: Locale::Finnish,
+            notation: Notation::RowCol,
+        };
+        let html = walkthrough.render("Arvaus", WalkthroughFormat::Html, options);
+        assert!(html.contains("<p>Aloita arvaus: R"));
+    }
+
+    #[test]
+    fn test_small() {
+        let walkthrough = Walkthrough::new(
+            &g("
+1.#4
+..#.
+#...
+4..#
+"),
+            &StrategyList::all(),
+        );
+        assert_eq!(walkthrough.error, None);
+        assert_eq!(walkthrough.solution().to_string(), "12#4\n21#3\n#432\n432#");
+
+        let html = walkthrough.to_html("Small");
+        assert_eq!(html.matches("<h2>Step ").count(), walkthrough.steps.len());
+        assert!(html.contains("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"164\" height=\"164\""));
+        assert!(html.contains("<h2>Solved</h2>"));
+    }
+
+    #[test]
+    fn test_already_solved() {
+        let puzzle = g("
+12
+21
+");
+        let walkthrough = Walkthrough::new(&puzzle, &StrategyList::all());
+        assert_eq!(walkthrough.steps, vec![]);
+        assert_eq!(walkthrough.error, None);
+        assert_eq!(walkthrough.solution(), &puzzle);
+        assert!(!walkthrough.to_markdown("Solved").contains("## Step"));
+    }
+
+    #[test]
+    fn test_stuck() {
+        let walkthrough = Walkthrough::new(
+            &g("
+11
+..
+"),
+            &StrategyList::all(),
+        );
+        let error = walkthrough.error.clone().unwrap();
+        let md = walkthrough.to_markdown("Invalid");
+        assert!(md.contains(&format!("\n## Stuck\n\n{}\n", error)));
+        assert!(!md.contains("## Solved"));
+    }
+
+    #[test]
+    fn test_without_uniqueness() {
+        let puzzle = g("
+#4..
+..#.
+.#..
+..4#
+");
+        assert_eq!(Walkthrough::new(&puzzle, &StrategyList::all()).error, None);
+
+        let walkthrough = Walkthrough::new(&puzzle, &StrategyList::all().without_uniqueness());
+        let error = walkthrough.error.unwrap();
+        assert!(matches!(error.ty, ValidationError::Ambiguous { solutions, .. } if solutions.len() == 2));
+    }
+}
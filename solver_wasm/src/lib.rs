@@ -9,11 +9,57 @@ use crate::wasm_grid::WasmGrid;
 use crate::wasm_solve_result::WasmSolveResult;
 use crate::wasm_validation_result::WasmValidationResult;
 use serde::{Deserialize, Serialize};
+use solver::coordinate::{NOTATIONS, Notation};
+use solver::messages::{LOCALES, Locale, MessageOptions};
 use solver::solve_result::{SolveResults, SolveType, ValidationError, ValidationResult};
-use solver::{generator, grid, puzzle_coding};
-use wasm_bindgen::prelude::*;
 use solver::solver::solve_round;
 use solver::strategy::Strategy;
+use solver::{daily, generator, grid, puzzle_coding};
+use std::cell::Cell;
+use wasm_bindgen::prelude::*;
+
+thread_local! {
+    static MESSAGE_OPTIONS: Cell<MessageOptions> = Cell::new(MessageOptions::default());
+}
+
+/* Locale and coordinate notation used for the res_display messages */
+pub(crate) fn message_options() -> MessageOptions {
+    MESSAGE_OPTIONS.with(|options| options.get())
+}
+
+#[wasm_bindgen]
+pub fn set_locale(locale: &str) -> Result<(), JsValue> {
+    let locale: Locale = locale.parse().map_err(|e: String| JsValue::from_str(&e))?;
+    MESSAGE_OPTIONS.with(|options| {
+        options.set(MessageOptions {
+            locale,
+            ..options.get()
+        })
+    });
+    Ok(())
+}
+
+#[wasm_bindgen]
+pub fn locales() -> Vec<String> {
+    LOCALES.iter().map(|locale| locale.code().to_string()).collect()
+}
+
+#[wasm_bindgen]
+pub fn set_notation(notation: &str) -> Result<(), JsValue> {
+    let notation: Notation = notation.parse().map_err(|e: String| JsValue::from_str(&e))?;
+    MESSAGE_OPTIONS.with(|options| {
+        options.set(MessageOptions {
+            notation,
+            ..options.get()
+        })
+    });
+    Ok(())
+}
+
+#[wasm_bindgen]
+pub fn notations() -> Vec<String> {
+    NOTATIONS.iter().map(|notation| notation.code().to_string()).collect()
+}
 
 #[wasm_bindgen]
 pub fn parse(puzzle: Vec<String>) -> Result<JsValue, JsValue> {
@@ -57,6 +103,20 @@ pub fn generate(input: JsValue) -> Result<JsValue, JsValue> {
     Ok(serde_wasm_bindgen::to_value(&out)?)
 }
 
+#[wasm_bindgen]
+pub fn daily(date: &str, target_difficulty: u8) -> Result<JsValue, JsValue> {
+    let date = date.parse::<daily::Date>().map_err(|e| JsValue::from_str(&e))?;
+    let grid = daily::daily(date, target_difficulty.into());
+
+    let grid_str = grid.to_string();
+    let out = GenerateOutput {
+        grid: grid.into(),
+        grid_str,
+    };
+
+    Ok(serde_wasm_bindgen::to_value(&out)?)
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 struct SolveOneReturn {
     grid: WasmGrid,
@@ -76,7 +136,10 @@ pub fn solve_one(input: JsValue) -> Result<JsValue, JsValue> {
         .unwrap_or(0);
     Ok(serde_wasm_bindgen::to_value(&SolveOneReturn {
         grid: grid.into(),
-        res_display: res.as_ref().map(|ok| ok.to_string()).map_err(|err| err.to_string()),
+        res_display: res
+            .as_ref()
+            .map(|ok| ok.message().format(message_options()))
+            .map_err(|err| err.message().format(message_options())),
         res: res.map(|ok| ok.into()).map_err(|err| err.into()),
         difficulty,
     })?)
@@ -101,7 +164,7 @@ pub fn solve(input: JsValue, use_guesses: bool) -> Result<JsValue, JsValue> {
                 let was_solved = strat.ty == SolveType::PuzzleSolved;
                 res.push(SolveOneReturn {
                     grid: grid.clone().into(),
-                    res_display: Ok(strat.to_string()),
+                    res_display: Ok(strat.message().format(message_options())),
                     res: Ok(strat.into()),
                     difficulty,
                 });
@@ -119,7 +182,7 @@ pub fn solve(input: JsValue, use_guesses: bool) -> Result<JsValue, JsValue> {
                 res.push(SolveOneReturn {
                     grid: grid.clone().into(),
                     res: Err(e.clone().into()),
-                    res_display: Err(e.to_string()),
+                    res_display: Err(e.message().format(message_options())),
                     difficulty: 0,
                 });
                 break;
@@ -147,3 +210,20 @@ pub fn encode(input: JsValue) -> Result<String, JsValue> {
 
     Ok(puzzle_coding::encode(&grid))
 }
+
+#[wasm_bindgen]
+pub fn encode_format_1(input: JsValue) -> Result<String, JsValue> {
+    let grid: WasmGrid = serde_wasm_bindgen::from_value(input)?;
+    let grid: grid::Grid = grid.into();
+
+    puzzle_coding::encode_format_1(&grid).map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn share_url(input: JsValue, base: Option<String>) -> Result<String, JsValue> {
+    let grid: WasmGrid = serde_wasm_bindgen::from_value(input)?;
+    let grid: grid::Grid = grid.into();
+    let base = base.as_deref().unwrap_or(puzzle_coding::DEFAULT_SHARE_BASE);
+
+    Ok(puzzle_coding::share_url(base, &grid))
+}
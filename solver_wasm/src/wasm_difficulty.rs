@@ -10,13 +10,21 @@ pub struct WasmDifficulty {
     pub cross_compartment_ranges: bool,
     pub maintain_reqs_and_blocks: bool,
     pub sets: bool,
+    pub hidden_sets: bool,
     pub setti: bool,
     pub y_wing: bool,
+    pub xyz_wing: bool,
     pub x_wing: bool,
     pub swordfish: bool,
     pub n_fish: usize,
+    pub x_chain: bool,
+    pub finned_fish: bool,
+    pub xy_chain: bool,
     pub medusa: bool,
+    pub aic: bool,
     pub unique_requirement: bool,
+    pub pattern_overlay: bool,
+    pub forcing_chain: bool,
     pub short_guess_count: usize,
     pub long_guess_count: usize,
 }
@@ -31,13 +39,21 @@ impl From<Difficulty> for WasmDifficulty {
             cross_compartment_ranges: value.cross_compartment_ranges,
             maintain_reqs_and_blocks: value.maintain_reqs_and_blocks,
             sets: value.sets,
+            hidden_sets: value.hidden_sets,
             setti: value.setti,
             y_wing: value.y_wing,
+            xyz_wing: value.xyz_wing,
             x_wing: value.x_wing,
             swordfish: value.swordfish,
             n_fish: value.n_fish,
+            x_chain: value.x_chain,
+            finned_fish: value.finned_fish,
+            xy_chain: value.xy_chain,
             medusa: value.medusa,
+            aic: value.aic,
             unique_requirement: value.unique_requirement,
+            pattern_overlay: value.pattern_overlay,
+            forcing_chain: value.forcing_chain,
             short_guess_count: value.short_guess_count,
             long_guess_count: value.long_guess_count,
         }
@@ -54,13 +70,21 @@ impl From<WasmDifficulty> for Difficulty {
             cross_compartment_ranges: value.cross_compartment_ranges,
             maintain_reqs_and_blocks: value.maintain_reqs_and_blocks,
             sets: value.sets,
+            hidden_sets: value.hidden_sets,
             setti: value.setti,
             y_wing: value.y_wing,
+            xyz_wing: value.xyz_wing,
             x_wing: value.x_wing,
             swordfish: value.swordfish,
             n_fish: value.n_fish,
+            x_chain: value.x_chain,
+            finned_fish: value.finned_fish,
+            xy_chain: value.xy_chain,
             medusa: value.medusa,
+            aic: value.aic,
             unique_requirement: value.unique_requirement,
+            pattern_overlay: value.pattern_overlay,
+            forcing_chain: value.forcing_chain,
             short_guess_count: value.short_guess_count,
             long_guess_count: value.long_guess_count,
         }
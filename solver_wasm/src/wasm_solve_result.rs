@@ -10,6 +10,7 @@ use std::rc::Rc;
 #[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub struct WasmSolveMetadata {
     pub colors: Vec<Vec<(Point, u8)>>,
+    pub lines: Vec<Vec<(bool, usize)>>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -31,12 +32,20 @@ pub enum WasmSolveType {
     RequiredRange,
     Sets(usize),
     RequiredAndForbidden,
+    HiddenSets(Vec<Point>, HashSet<u8>),
     RowColBrute,
     Setti(HashSet<u8>),
     YWing(Point, u8),
+    XyzWing(Point, Vec<(Point, u8)>),
     Fish(usize),
+    XChain(u8, Vec<Point>),
+    FinnedFish(usize, bool, u8, Vec<Point>),
+    XyChain(Vec<Point>, Vec<(Point, u8)>),
     Medusa,
+    Aic(Vec<(Point, u8)>, Vec<(Point, u8)>),
     UniqueRequirement(WasmUrResult),
+    PatternOverlay(u8, Vec<Point>, Vec<Point>),
+    ForcingChain(Vec<(Point, u8)>, Vec<(Point, u8)>, Vec<(Point, u8)>),
     StartGuess(Point, u8),
     GuessStep(Point, u8, Vec<(WasmGrid, WasmSolveResult, String)>, WasmGrid),
     EndGuess(WasmValidationResult),
@@ -52,13 +61,19 @@ pub struct WasmSolveResult {
 
 impl From<SolveMetadata> for WasmSolveMetadata {
     fn from(value: SolveMetadata) -> Self {
-        WasmSolveMetadata { colors: value.colors }
+        WasmSolveMetadata {
+            colors: value.colors,
+            lines: value.lines,
+        }
     }
 }
 
 impl From<WasmSolveMetadata> for SolveMetadata {
     fn from(value: WasmSolveMetadata) -> Self {
-        SolveMetadata { colors: value.colors }
+        SolveMetadata {
+            colors: value.colors,
+            lines: value.lines,
+        }
     }
 }
 
@@ -98,12 +113,22 @@ impl From<SolveType> for WasmSolveType {
             SolveType::RequiredRange => WasmSolveType::RequiredRange,
             SolveType::Sets(n) => WasmSolveType::Sets(n),
             SolveType::RequiredAndForbidden => WasmSolveType::RequiredAndForbidden,
+            SolveType::HiddenSets(list, set) => WasmSolveType::HiddenSets(list, set.into()),
             SolveType::RowColBrute => WasmSolveType::RowColBrute,
             SolveType::Setti(set) => WasmSolveType::Setti(set.into()),
             SolveType::YWing(pos, n) => WasmSolveType::YWing(pos, n),
             SolveType::Fish(n) => WasmSolveType::Fish(n),
+            SolveType::XyzWing(pos, removed) => WasmSolveType::XyzWing(pos, removed),
+            SolveType::XChain(n, list) => WasmSolveType::XChain(n, list),
+            SolveType::FinnedFish(size, sashimi, n, list) => WasmSolveType::FinnedFish(size, sashimi, n, list),
+            SolveType::XyChain(list, removed) => WasmSolveType::XyChain(list, removed),
             SolveType::Medusa => WasmSolveType::Medusa,
+            SolveType::Aic(chain, removed) => WasmSolveType::Aic(chain, removed),
             SolveType::UniqueRequirement(res) => WasmSolveType::UniqueRequirement(res.into()),
+            SolveType::PatternOverlay(n, removed, placed) => WasmSolveType::PatternOverlay(n, removed, placed),
+            SolveType::ForcingChain(branches, removed, placed) => {
+                WasmSolveType::ForcingChain(branches, removed, placed)
+            }
             SolveType::StartGuess((x, y), n) => WasmSolveType::StartGuess((x, y), n),
             SolveType::GuessStep((x, y), n, steps, grid) => WasmSolveType::GuessStep(
                 (x, y),
@@ -111,7 +136,7 @@ impl From<SolveType> for WasmSolveType {
                 steps
                     .iter()
                     .cloned()
-                    .map(|(l, r)| (l.into(), r.clone().into(), r.to_string()))
+                    .map(|(l, r)| (l.into(), r.clone().into(), r.message().format(crate::message_options())))
                     .collect(),
                 grid.into(),
             ),
@@ -132,12 +157,22 @@ impl From<WasmSolveType> for SolveType {
             WasmSolveType::RequiredRange => SolveType::RequiredRange,
             WasmSolveType::Sets(n) => SolveType::Sets(n),
             WasmSolveType::RequiredAndForbidden => SolveType::RequiredAndForbidden,
+            WasmSolveType::HiddenSets(list, set) => SolveType::HiddenSets(list, set.into()),
             WasmSolveType::RowColBrute => SolveType::RowColBrute,
             WasmSolveType::Setti(set) => SolveType::Setti(set.into()),
             WasmSolveType::YWing(pos, n) => SolveType::YWing(pos, n),
             WasmSolveType::Fish(n) => SolveType::Fish(n),
+            WasmSolveType::XyzWing(pos, removed) => SolveType::XyzWing(pos, removed),
+            WasmSolveType::XChain(n, list) => SolveType::XChain(n, list),
+            WasmSolveType::FinnedFish(size, sashimi, n, list) => SolveType::FinnedFish(size, sashimi, n, list),
+            WasmSolveType::XyChain(list, removed) => SolveType::XyChain(list, removed),
             WasmSolveType::Medusa => SolveType::Medusa,
+            WasmSolveType::Aic(chain, removed) => SolveType::Aic(chain, removed),
             WasmSolveType::UniqueRequirement(res) => SolveType::UniqueRequirement(res.into()),
+            WasmSolveType::PatternOverlay(n, removed, placed) => SolveType::PatternOverlay(n, removed, placed),
+            WasmSolveType::ForcingChain(branches, removed, placed) => {
+                SolveType::ForcingChain(branches, removed, placed)
+            }
             WasmSolveType::StartGuess((x, y), n) => SolveType::StartGuess((x, y), n),
             WasmSolveType::GuessStep((x, y), n, steps, grid) => SolveType::GuessStep(
                 (x, y),
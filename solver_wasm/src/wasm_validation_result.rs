@@ -42,6 +42,7 @@ pub enum WasmValidationError {
     },
     Ambiguous {
         cells: Vec<Point>,
+        solutions: Vec<Vec<u8>>,
     },
     NoSolutions,
     OutOfStrats,
@@ -64,7 +65,7 @@ impl From<ValidationError> for WasmValidationError {
             ValidationError::RequirementBlockerConflict { vertical, index, number, } => WasmValidationError::RequirementBlockerConflict { vertical, index, number, },
             ValidationError::RequiredNumberMissing { vertical, index, number, } => WasmValidationError::RequiredNumberMissing { vertical, index, number, },
             ValidationError::BlockedNumberPresent { vertical, index, number, } => WasmValidationError::BlockedNumberPresent { vertical, index, number, },
-            ValidationError::Ambiguous { cells, } => WasmValidationError::Ambiguous { cells, },
+            ValidationError::Ambiguous { cells, solutions, } => WasmValidationError::Ambiguous { cells, solutions, },
             ValidationError::NoSolutions => WasmValidationError::NoSolutions,
             ValidationError::OutOfStrats => WasmValidationError::OutOfStrats,
         }
@@ -82,7 +83,7 @@ impl From<WasmValidationError> for ValidationError {
             WasmValidationError::RequirementBlockerConflict { vertical, index, number, } => ValidationError::RequirementBlockerConflict { vertical, index, number, },
             WasmValidationError::RequiredNumberMissing { vertical, index, number, } => ValidationError::RequiredNumberMissing { vertical, index, number, },
             WasmValidationError::BlockedNumberPresent { vertical, index, number, } => ValidationError::BlockedNumberPresent { vertical, index, number, },
-            WasmValidationError::Ambiguous { cells, } => ValidationError::Ambiguous { cells, },
+            WasmValidationError::Ambiguous { cells, solutions, } => ValidationError::Ambiguous { cells, solutions, },
             WasmValidationError::NoSolutions => ValidationError::NoSolutions,
             WasmValidationError::OutOfStrats => ValidationError::OutOfStrats,
         }
@@ -0,0 +1,227 @@
+use crossterm::style::{Color, Stylize};
+use solver::grid::{Cell, Grid, Point};
+use solver::solve_result::highlight;
+use std::fmt::{Display, Formatter};
+
+pub const HIGHLIGHT_COLORS: [Color; 4] = [Color::DarkGreen, Color::DarkMagenta, Color::DarkCyan, Color::DarkYellow];
+const BLACK_CELL: Color = Color::DarkGrey;
+
+/* Grid with box-drawing borders and 3x3 candidate sub-grids, highlighting the given colors.
+ * Without color, black cells are shaded and highlighted candidates are put in brackets, with `|` between two
+ * neighbouring highlighted candidates. */
+pub struct AnsiGrid<'a> {
+    pub grid: &'a Grid,
+    pub colors: &'a [Vec<(Point, u8)>],
+    pub color: bool,
+}
+
+struct Glyph {
+    c: char,
+    fg: Option<Color>,
+    bg: Option<Color>,
+    bold: bool,
+}
+
+impl AnsiGrid<'_> {
+    fn highlight(&self, pos: Point, num: u8) -> Option<Color> {
+        highlight(self.colors, &HIGHLIGHT_COLORS, pos, num)
+    }
+
+    fn is_highlighted(&self, pos: Point, num: u8) -> bool {
+        match *self.grid.get_cell(pos) {
+            Cell::Solution(n) => num == 5 && self.highlight(pos, n).is_some(),
+            Cell::Indeterminate(set) => set.contains(num) && self.highlight(pos, num).is_some(),
+            _ => false,
+        }
+    }
+
+    fn glyph(&self, pos: Point, num: u8) -> Glyph {
+        let center = num == 5;
+        let digit = |n: u8| if center { (b'0' + n) as char } else { ' ' };
+        let shade = if self.color { ' ' } else { '░' };
+        let plain = |c| Glyph {
+            c,
+            fg: None,
+            bg: None,
+            bold: false,
+        };
+        match *self.grid.get_cell(pos) {
+            Cell::Black => Glyph {
+                c: shade,
+                fg: None,
+                bg: Some(BLACK_CELL),
+                bold: false,
+            },
+            Cell::Blocker(n) => Glyph {
+                c: if center { digit(n) } else { shade },
+                fg: Some(Color::White),
+                bg: Some(BLACK_CELL),
+                bold: true,
+            },
+            Cell::Requirement(n) => Glyph {
+                bold: true,
+                ..plain(digit(n))
+            },
+            Cell::Solution(n) => Glyph {
+                c: digit(n),
+                fg: Some(Color::Cyan),
+                bg: self.highlight(pos, n).filter(|_| center),
+                bold: true,
+            },
+            Cell::Indeterminate(set) if set.contains(num) => match self.highlight(pos, num) {
+                Some(bg) => Glyph {
+                    c: (b'0' + num) as char,
+                    fg: Some(Color::White),
+                    bg: Some(bg),
+                    bold: false,
+                },
+                None => Glyph {
+                    c: (b'0' + num) as char,
+                    fg: Some(Color::DarkYellow),
+                    bg: None,
+                    bold: false,
+                },
+            },
+            Cell::Indeterminate(_) => plain(' '),
+        }
+    }
+
+    fn write_glyph(&self, f: &mut Formatter<'_>, glyph: Glyph) -> std::fmt::Result {
+        if !self.color {
+            return write!(f, "{}", glyph.c);
+        }
+        let mut styled = glyph.c.stylize();
+        if let Some(fg) = glyph.fg {
+            styled = styled.with(fg);
+        }
+        if let Some(bg) = glyph.bg {
+            styled = styled.on(bg);
+        }
+        if glyph.bold {
+            styled = styled.bold();
+        }
+        write!(f, "{}", styled)
+    }
+
+    /* One row of a cell's candidates, with the brackets marking highlights in between when there's no color */
+    fn write_cell_row(&self, f: &mut Formatter<'_>, pos: Point, row: u8) -> std::fmt::Result {
+        let nums = [row * 3 + 1, row * 3 + 2, row * 3 + 3];
+        if self.color {
+            for num in nums {
+                self.write_glyph(f, self.glyph(pos, num))?;
+            }
+            return Ok(());
+        }
+        let filler = match self.grid.get_cell(pos) {
+            Cell::Black | Cell::Blocker(_) => '░',
+            _ => ' ',
+        };
+        let marked = nums.map(|num| self.is_highlighted(pos, num));
+        for i in 0..=nums.len() {
+            let separator = match (i > 0 && marked[i - 1], i < nums.len() && marked[i]) {
+                (false, false) => filler,
+                (true, false) => ']',
+                (false, true) => '[',
+                (true, true) => '|',
+            };
+            write!(f, "{}", separator)?;
+            if i < nums.len() {
+                self.write_glyph(f, self.glyph(pos, nums[i]))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_border(&self, f: &mut Formatter<'_>, left: char, middle: char, right: char) -> std::fmt::Result {
+        let width = if self.color { 3 } else { 7 };
+        write!(f, "{}", left)?;
+        for x in 0..self.grid.x {
+            write!(f, "{}", "─".repeat(width))?;
+            write!(f, "{}", if x + 1 == self.grid.x { right } else { middle })?;
+        }
+        writeln!(f)
+    }
+}
+
+impl Display for AnsiGrid<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.write_border(f, '┌', '┬', '┐')?;
+        for y in 0..self.grid.y {
+            for row in 0..3 {
+                write!(f, "│")?;
+                for x in 0..self.grid.x {
+                    self.write_cell_row(f, (x, y), row)?;
+                    write!(f, "│")?;
+                }
+                writeln!(f)?;
+            }
+            if y + 1 == self.grid.y {
+                self.write_border(f, '└', '┴', '┘')?;
+            } else {
+                self.write_border(f, '├', '┼', '┤')?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(grid: &str, colors: &[Vec<(Point, u8)>], color: bool) -> String {
+        let grid = Grid::parse_oneline(grid).unwrap();
+        AnsiGrid {
+            grid: &grid,
+            colors,
+            color,
+        }
+        .to_string()
+    }
+
+    #[test]
+    fn test_plain() {
+        let text = render("1.\n#b", &[vec![((1, 0), 2)]], false);
+        assert_eq!(
+            text,
+            "\
+┌───────┬───────┐
+│       │ 1[2]  │
+│   1   │       │
+│       │       │
+├───────┼───────┤
+│░░░░░░░│░░░░░░░│
+│░░░░░░░│░░░2░░░│
+│░░░░░░░│░░░░░░░│
+└───────┴───────┘
+"
+        );
+    }
+
+    #[test]
+    fn test_plain_neighbouring_highlights() {
+        let mut grid = Grid::parse_oneline("...\n...\n...").unwrap();
+        grid.set_cell((0, 0), Cell::Solution(2));
+        let colors = vec![vec![((0, 0), 2), ((1, 0), 1)], vec![((1, 0), 2), ((1, 0), 3)]];
+        let text = AnsiGrid {
+            grid: &grid,
+            colors: &colors,
+            color: false,
+        }
+        .to_string();
+        let lines = text.lines().collect::<Vec<_>>();
+        assert_eq!(lines[1], "│       │[1|2|3]│ 1 2 3 │");
+        assert_eq!(lines[2], "│  [2]  │       │       │");
+    }
+
+    #[test]
+    fn test_color() {
+        let colors = vec![vec![((1, 0), 2)]];
+        let text = render("1.\n#b", &colors, true);
+        assert!(text.starts_with("┌───┬───┐\n"));
+        assert!(text.contains(&'2'.with(Color::White).on(HIGHLIGHT_COLORS[0]).to_string()));
+        assert!(text.contains(&'1'.with(Color::DarkYellow).to_string()));
+        assert!(text.contains(&'2'.with(Color::White).on(BLACK_CELL).bold().to_string()));
+        assert!(!text.contains('░'));
+    }
+}
@@ -0,0 +1,375 @@
+use crate::pdf;
+use solver::difficulty::get_puzzle_difficulty;
+use solver::grid::Grid;
+use solver::mistakes::solve_puzzle;
+use solver::strategy::StrategyList;
+use solver::svg::{grid_group, svg_document, SvgOptions};
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/* A4 in points */
+pub const PAGE_WIDTH: usize = 595;
+pub const PAGE_HEIGHT: usize = 842;
+const MARGIN: usize = 40;
+const HEADER: usize = 50;
+const LABEL: usize = 24;
+const GAP: usize = 20;
+/* Smaller grids are too small to write on */
+const MIN_CELL_SIZE: usize = 10;
+const MAX_STARS: usize = 7;
+
+pub struct BookletPuzzle {
+    pub puzzle: Grid,
+    pub solution: Grid,
+    pub stars: usize,
+}
+
+pub enum Item {
+    Text {
+        x: usize,
+        y: usize,
+        size: usize,
+        bold: bool,
+        text: String,
+    },
+    Star {
+        x: usize,
+        y: usize,
+        radius: usize,
+        filled: bool,
+    },
+    Grid {
+        x: usize,
+        y: usize,
+        cell_size: usize,
+        grid: Grid,
+        solution: bool,
+    },
+}
+
+pub type Page = Vec<Item>;
+
+impl BookletPuzzle {
+    pub fn new(puzzle: Grid) -> Result<BookletPuzzle, String> {
+        let solution = solve_puzzle(&puzzle).map_err(|e| e.to_string())?;
+        let stars = get_puzzle_difficulty(&puzzle, &StrategyList::all())
+            .map(|difficulty| difficulty.star_count)
+            .ok_or_else(|| "Could not rate the puzzle".to_string())?;
+        Ok(BookletPuzzle {
+            puzzle,
+            solution,
+            stars,
+        })
+    }
+}
+
+/* Puzzles are separated by blank lines, or given one per line in one of the single-line formats */
+pub fn parse_puzzles(input: &str) -> Result<Vec<Grid>, String> {
+    let mut puzzles = Vec::new();
+    let mut block = Vec::new();
+    /* lines() also strips the \r of CRLF line endings */
+    for line in input.lines().map(str::trim).chain([""]) {
+        if !line.is_empty() {
+            block.push(line);
+            continue;
+        }
+        let Some(first) = block.first() else {
+            continue;
+        };
+        if first.chars().count() == block.len() {
+            puzzles.push(Grid::parse(block.iter().map(|line| line.to_string()).collect())?);
+        } else {
+            for line in &block {
+                puzzles.push(Grid::parse(vec![line.to_string()])?);
+            }
+        }
+        block.clear();
+    }
+    Ok(puzzles)
+}
+
+fn slots(per_page: usize) -> (usize, usize) {
+    let cols = per_page.isqrt() + usize::from(per_page.isqrt().pow(2) < per_page);
+    let rows = per_page.div_ceil(cols);
+    (cols, rows)
+}
+
+/* Width and height of one puzzle, or None if the puzzles don't fit on the page */
+fn slot_size(per_page: usize) -> Option<(usize, usize)> {
+    if per_page == 0 {
+        return None;
+    }
+    let (cols, rows) = slots(per_page);
+    let width = (PAGE_WIDTH - 2 * MARGIN).checked_sub((cols - 1).checked_mul(GAP)?)? / cols;
+    let height = (PAGE_HEIGHT - 2 * MARGIN - HEADER).checked_sub((rows - 1).checked_mul(GAP)?)? / rows;
+    Some((width, height))
+}
+
+fn cell_size((width, height): (usize, usize), size: usize) -> usize {
+    width.min(height.saturating_sub(LABEL)).saturating_sub(4) / size
+}
+
+fn layout_page(title: &str, entries: &[(usize, &Grid, usize)], per_page: usize, solution: bool) -> Page {
+    let mut page = vec![Item::Text {
+        x: MARGIN,
+        y: MARGIN + 24,
+        size: 24,
+        bold: true,
+        text: title.to_string(),
+    }];
+
+    let (cols, _) = slots(per_page);
+    let (slot_width, slot_height) = slot_size(per_page).unwrap();
+
+    for (i, &(number, grid, stars)) in entries.iter().enumerate() {
+        let left = MARGIN + (i % cols) * (slot_width + GAP);
+        let top = MARGIN + HEADER + (i / cols) * (slot_height + GAP);
+        let cell_size = cell_size((slot_width, slot_height), grid.x.max(grid.y));
+
+        page.push(Item::Text {
+            x: left,
+            y: top + 14,
+            size: 14,
+            bold: true,
+            text: format!("#{}", number),
+        });
+        if !solution {
+            for star in 0..MAX_STARS {
+                page.push(Item::Star {
+                    x: left + 48 + star * 14,
+                    y: top + 9,
+                    radius: 6,
+                    filled: star < stars,
+                });
+            }
+        }
+        page.push(Item::Grid {
+            x: left,
+            y: top + LABEL,
+            cell_size,
+            grid: grid.clone(),
+            solution,
+        });
+    }
+
+    page
+}
+
+/* Puzzle pages followed by the solutions, both numbered from 1 */
+pub fn layout(title: &str, puzzles: &[BookletPuzzle], per_page: usize) -> Result<Vec<Page>, String> {
+    let size = puzzles.iter().map(|p| p.puzzle.x.max(p.puzzle.y)).max().unwrap_or(1);
+    if slot_size(per_page).is_none_or(|slot| cell_size(slot, size) < MIN_CELL_SIZE) {
+        return Err(format!("{} puzzles do not fit on a page", per_page));
+    }
+    let mut pages = Vec::new();
+
+    let numbered = puzzles.iter().enumerate().map(|(i, p)| (i + 1, p)).collect::<Vec<_>>();
+    for chunk in numbered.chunks(per_page) {
+        let entries = chunk.iter().map(|(n, p)| (*n, &p.puzzle, p.stars)).collect::<Vec<_>>();
+        pages.push(layout_page(title, &entries, per_page, false));
+    }
+    let solutions_title = format!("{} - Solutions", title);
+    for chunk in numbered.chunks(per_page) {
+        let entries = chunk
+            .iter()
+            .map(|(n, p)| (*n, &p.solution, p.stars))
+            .collect::<Vec<_>>();
+        pages.push(layout_page(&solutions_title, &entries, per_page, true));
+    }
+
+    Ok(pages)
+}
+
+/* Corners of a five-pointed star, starting from the top */
+pub fn star_points(x: usize, y: usize, radius: usize) -> Vec<(f64, f64)> {
+    (0..10)
+        .map(|i| {
+            let r = if i % 2 == 0 { radius as f64 } else { radius as f64 * 0.4 };
+            let angle = std::f64::consts::PI * (i as f64) / 5.0;
+            (x as f64 + r * angle.sin(), y as f64 - r * angle.cos())
+        })
+        .collect()
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+pub fn page_to_svg(page: &Page) -> String {
+    let mut body = String::new();
+    for item in page {
+        match item {
+            Item::Text { x, y, size, bold, text } => writeln!(
+                body,
+                "<text x=\"{}\" y=\"{}\" font-size=\"{}\" font-family=\"sans-serif\"{}>{}</text>",
+                x,
+                y,
+                size,
+                if *bold { " font-weight=\"bold\"" } else { "" },
+                escape_xml(text)
+            )
+            .unwrap(),
+            Item::Star { x, y, radius, filled } => {
+                let points = star_points(*x, *y, *radius)
+                    .iter()
+                    .map(|(x, y)| format!("{:.1},{:.1}", x, y))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                writeln!(
+                    body,
+                    "<polygon points=\"{}\" fill=\"{}\" stroke=\"black\" stroke-width=\"0.8\"/>",
+                    points,
+                    if *filled { "black" } else { "none" }
+                )
+                .unwrap()
+            }
+            Item::Grid {
+                x,
+                y,
+                cell_size,
+                grid,
+                solution,
+            } => {
+                let options = SvgOptions {
+                    cell_size: *cell_size,
+                    candidates: false,
+                    solutions: *solution,
+                };
+                body.push_str(&grid_group(grid, &[], &options, *x, *y));
+            }
+        }
+    }
+    svg_document(PAGE_WIDTH, PAGE_HEIGHT, &body)
+}
+
+/* PDF output is a single file, SVG output is written as one file per page: booklet-1.svg, booklet-2.svg, ... */
+pub fn write_booklet(input: &Path, output: &Path, title: &str, per_page: usize) -> Result<Vec<PathBuf>, String> {
+    let text = fs::read_to_string(input).map_err(|e| format!("Failed to read {}: {}", input.display(), e))?;
+    let puzzles = parse_puzzles(&text)?
+        .into_iter()
+        .enumerate()
+        .map(|(i, grid)| BookletPuzzle::new(grid).map_err(|e| format!("Failed to solve puzzle #{}: {}", i + 1, e)))
+        .collect::<Result<Vec<_>, _>>()?;
+    if puzzles.is_empty() {
+        return Err(format!("No puzzles found in {}", input.display()));
+    }
+    let pages = layout(title, &puzzles, per_page)?;
+
+    let files = if output.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("pdf")) {
+        vec![(output.to_path_buf(), pdf::write_pdf(&pages))]
+    } else {
+        let stem = output.file_stem().and_then(|s| s.to_str()).unwrap_or("booklet");
+        pages
+            .iter()
+            .enumerate()
+            .map(|(i, page)| {
+                let path = output.with_file_name(format!("{}-{}.svg", stem, i + 1));
+                (path, page_to_svg(page).into_bytes())
+            })
+            .collect()
+    };
+
+    for (path, contents) in &files {
+        fs::write(path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str = "
+12#.7...6
+.#.4..3..
+.#.#i....
+7..3.....
+9#3#.#8#7
+.....1..4
+....h#.#.
+..9..8.#.
+5...4.#32
+";
+
+    #[test]
+    fn test_parse_puzzles() {
+        let encoded = solver::puzzle_coding::encode(&Grid::parse_oneline(PUZZLE).unwrap());
+        let input = format!("{}\n\n{}\n{}\n", PUZZLE, encoded, encoded);
+        let puzzles = parse_puzzles(&input).unwrap();
+        assert_eq!(puzzles.len(), 3);
+        assert!(puzzles.iter().all(|grid| grid == &puzzles[0]));
+    }
+
+    const SMALL_PUZZLE: &str = "
+1.#4
+..#.
+#...
+4..#
+";
+
+    #[test]
+    fn test_parse_crlf() {
+        let encoded = solver::puzzle_coding::encode(&Grid::parse_oneline(SMALL_PUZZLE).unwrap());
+        let input = format!("{}\n\n  \n{}\n\n{}\n", PUZZLE, SMALL_PUZZLE, encoded).replace('\n', "\r\n");
+        let puzzles = parse_puzzles(&input).unwrap();
+        assert_eq!(puzzles.iter().map(|grid| grid.x).collect::<Vec<_>>(), vec![9, 4, 4]);
+        assert_eq!(puzzles[1], puzzles[2]);
+        assert_eq!(parse_puzzles("\r\n\r\n").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_layout_sizes() {
+        let small = BookletPuzzle::new(Grid::parse_oneline(SMALL_PUZZLE).unwrap()).unwrap();
+        assert_eq!(small.solution.to_string(), "12#4\n21#3\n#432\n432#");
+        let large = BookletPuzzle::new(Grid::parse_oneline(PUZZLE).unwrap()).unwrap();
+        let cell_sizes = |pages: &[Page]| {
+            pages[0]
+                .iter()
+                .filter_map(|item| match item {
+                    Item::Grid { cell_size, .. } => Some(*cell_size),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        /* the cells of smaller grids are larger, and more of them fit on a page */
+        let pages = layout("Test", &[small, large], 4).unwrap();
+        assert_eq!(cell_sizes(&pages), vec![60, 27]);
+        let small = BookletPuzzle::new(Grid::parse_oneline(SMALL_PUZZLE).unwrap()).unwrap();
+        assert_eq!(cell_sizes(&layout("Test", &[small], 25).unwrap()), vec![20]);
+    }
+
+    #[test]
+    fn test_layout() {
+        let puzzles = (0..5)
+            .map(|_| BookletPuzzle::new(Grid::parse_oneline(PUZZLE).unwrap()).unwrap())
+            .collect::<Vec<_>>();
+        let pages = layout("Test", &puzzles, 4).unwrap();
+        let grids = |page: &Page| page.iter().filter(|item| matches!(item, Item::Grid { .. })).count();
+        let stars = |page: &Page| page.iter().filter(|item| matches!(item, Item::Star { .. })).count();
+
+        assert_eq!(pages.iter().map(grids).collect::<Vec<_>>(), vec![4, 1, 4, 1]);
+        assert_eq!(stars(&pages[0]), 4 * MAX_STARS);
+        assert_eq!(stars(&pages[2]), 0);
+        assert!(pdf::write_pdf(&pages).starts_with(b"%PDF-1.4"));
+        assert_eq!(layout("Test", &puzzles, 16).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_layout_too_many() {
+        let puzzles = vec![BookletPuzzle::new(Grid::parse_oneline(PUZZLE).unwrap()).unwrap()];
+        for per_page in [0, 25, 1000, usize::MAX] {
+            assert_eq!(
+                layout("Test", &puzzles, per_page).err(),
+                Some(format!("{} puzzles do not fit on a page", per_page))
+            );
+        }
+    }
+
+    #[test]
+    fn test_unsolvable() {
+        assert!(BookletPuzzle::new(Grid::parse_oneline("..\n..").unwrap()).is_err());
+        assert!(BookletPuzzle::new(Grid::parse_oneline("11\n..").unwrap()).is_err());
+    }
+}
@@ -1,13 +1,31 @@
+use crate::ansi::AnsiGrid;
 use clap::CommandFactory;
 use clap::Parser;
+use solver::cnf::{decode_assignment, Cnf};
+use solver::coordinate::Notation;
+use solver::daily::{daily, Date};
 use solver::difficulty::get_puzzle_difficulty;
+use solver::game::Game;
 use solver::generator;
-use solver::grid::Grid;
-use solver::solve_result::{SolveResults, SolveType};
-use solver::solver::solve_round;
+use solver::grid::{Cell, Grid};
+use solver::library::{Filter, GeneratorParams, LibraryEntry};
+use solver::messages::{Locale, MessageOptions};
+use solver::minizinc::{decode_output, to_minizinc};
+use solver::puzzle_coding::{encode_format_1, share_url, DEFAULT_SHARE_BASE};
+use solver::solve_result::{SolveResults, SolveType, ValidationError};
+use solver::solver::run_strat;
 use solver::strategy::StrategyList;
+use solver::walkthrough::{Walkthrough, WalkthroughFormat};
+use std::io::IsTerminal;
+use std::path::PathBuf;
 use std::process::ExitCode;
 
+mod ansi;
+mod booklet;
+mod library;
+mod pdf;
+mod tui;
+
 pub fn cli() -> ExitCode {
     /// Generate or solve a srt8ts puzzle.
     #[derive(Parser, Debug)]
@@ -16,9 +34,21 @@ pub fn cli() -> ExitCode {
         /// Generate a puzzle
         #[arg(long, default_value_t = false)]
         generate: bool,
+        /// Generate the puzzle of the day for a date (YYYY-MM-DD, default today in UTC)
+        #[arg(long, value_name = "DATE", num_args = 0..=1, default_missing_value = "today")]
+        daily: Option<String>,
         /// Solve a puzzle
         #[arg(long, default_value_t = false)]
         solve: bool,
+        /// Play a puzzle in the terminal
+        #[arg(long, default_value_t = false)]
+        play: bool,
+        /// Export the puzzles in a file to a printable booklet with solutions
+        #[arg(long, value_name = "FILE")]
+        booklet: Option<PathBuf>,
+        /// List, import or export the puzzles of a library file (JSON Lines)
+        #[arg(long, value_name = "FILE")]
+        library: Option<PathBuf>,
         /// generate: Size of the puzzle
         #[arg(long, default_value_t = 9)]
         size: usize,
@@ -28,36 +58,148 @@ pub fn cli() -> ExitCode {
         /// generate: Amount of numbers inside black squares
         #[arg(long, default_value_t = 5)]
         blocker_num_count: usize,
-        /// generate: Target difficulty in stars.
+        /// generate, daily: Target difficulty in stars.
         #[arg(long, default_value_t = 5)]
         target_difficulty: usize,
         /// generate: Should the puzzle be unsymmetric
         #[arg(long, default_value_t = false)]
         not_symmetric: bool,
-        /// solve: Puzzle to be solved
+        /// generate: Append the puzzle with its generator parameters to a library file
+        #[arg(long, value_name = "FILE")]
+        save: Option<PathBuf>,
+        /// solve, play: Puzzle to be solved
         #[arg(long)]
         puzzle: Option<String>,
+        /// solve: Print each step with the grid before it, highlighting the cells it uses
+        #[arg(long, default_value_t = false)]
+        explain: bool,
+        /// solve: Don't assume the puzzle to have a unique solution, and report ambiguous puzzles with two solutions
+        #[arg(long, default_value_t = false)]
+        no_uniqueness: bool,
+        /// solve: Write a walkthrough of the solution to a Markdown or HTML (.html) file
+        #[arg(long, value_name = "FILE")]
+        walkthrough: Option<PathBuf>,
+        /// solve: Write the puzzle as a SAT problem in the DIMACS CNF format
+        #[arg(long, value_name = "FILE")]
+        cnf: Option<PathBuf>,
+        /// solve: Write the puzzle as a MiniZinc model
+        #[arg(long, value_name = "FILE")]
+        minizinc: Option<PathBuf>,
+        /// solve: Print the grid from a SAT solver model or MiniZinc output for the puzzle instead of solving it
+        #[arg(long, value_name = "FILE")]
+        decode: Option<PathBuf>,
+        /// solve, play: Language of the step explanations (en, fi or de)
+        #[arg(long, default_value = "en")]
+        locale: Locale,
+        /// solve, play: Cell coordinates in explanations: xy for (x, y), r1c1 or a1
+        #[arg(long, default_value = "xy")]
+        notation: Notation,
+        /// generate, daily, solve: Also print the puzzle in the str8ts.com format 1 and as a link
+        #[arg(long, default_value_t = false)]
+        share: bool,
+        /// generate, daily, solve: Address of the solver for --share links
+        #[arg(long, default_value = DEFAULT_SHARE_BASE)]
+        share_base: String,
+        /// booklet: Output file, either .pdf or .svg (one file per page)
+        #[arg(long, default_value = "booklet.pdf")]
+        output: PathBuf,
+        /// booklet: Title printed on every page
+        #[arg(long, default_value = "Str8ts")]
+        title: String,
+        /// booklet: Puzzles per page
+        #[arg(long, default_value_t = 4)]
+        per_page: usize,
+        /// library: Add the puzzles of a text file or another library (.jsonl)
+        #[arg(long, value_name = "FILE")]
+        import: Option<PathBuf>,
+        /// library: Write the matching puzzles to a library (.jsonl) or one per line to any other file
+        #[arg(long, value_name = "FILE")]
+        export: Option<PathBuf>,
+        /// library: Tags for imported puzzles
+        #[arg(long, value_delimiter = ',')]
+        tags: Vec<String>,
+        /// library: Author of imported puzzles
+        #[arg(long)]
+        author: Option<String>,
+        /// library: Only list or export puzzles with this many stars
+        #[arg(long)]
+        stars: Option<usize>,
+        /// library: Only list or export puzzles needing this technique, e.g. x_wing or medusa
+        #[arg(long)]
+        technique: Option<String>,
+        /// library: Only list or export puzzles with this tag
+        #[arg(long)]
+        tag: Option<String>,
         #[arg(long, default_value_t = false)]
         silent: bool,
     }
 
     let Args {
         generate,
+        daily: daily_date,
         solve,
+        play,
         size,
         blocker_count,
         blocker_num_count,
         target_difficulty,
         not_symmetric,
+        save,
         puzzle,
+        explain,
+        no_uniqueness,
+        walkthrough,
+        cnf,
+        minizinc,
+        decode,
+        locale,
+        notation,
+        share,
+        share_base,
+        booklet,
+        output,
+        title,
+        per_page,
+        library,
+        import,
+        export,
+        tags,
+        author,
+        stars,
+        technique,
+        tag,
         silent,
     } = Args::parse();
 
     use log::info;
     env_logger::init_from_env(env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, "warn"));
 
-    if (generate && solve) || !(generate || solve) {
-        println!("Error: Pass either --generate or --solve\n");
+    let options = MessageOptions { locale, notation };
+
+    let print_share = |grid: &Grid| {
+        if share {
+            match encode_format_1(grid) {
+                Ok(encoded) => println!("{}", encoded),
+                Err(e) => println!("{}", e),
+            }
+            println!("{}", share_url(&share_base, grid));
+        }
+    };
+
+    if [
+        generate,
+        daily_date.is_some(),
+        solve,
+        play,
+        booklet.is_some(),
+        library.is_some(),
+    ]
+    .into_iter()
+    .filter(|&mode| mode)
+    .count()
+        != 1
+    {
+        println!("Error: Pass one of --generate, --daily, --solve, --play, --booklet or --library\n");
         let _ = Args::command().print_help();
         return ExitCode::FAILURE;
     }
@@ -72,10 +214,51 @@ pub fn cli() -> ExitCode {
             size, size, blocker_count, blocker_num_count
         );
 
-        let grid = generator::generator(size, blocker_count, blocker_num_count, target_difficulty, !not_symmetric);
+        let seed = generator::random_seed();
+        let grid =
+            generator::generator_loop(size, blocker_count, blocker_num_count, target_difficulty, !not_symmetric, seed);
         info!("Generated grid with difficulty {}", target_difficulty);
         info!("Strats required: {:#?}", get_puzzle_difficulty(&grid, &StrategyList::all()).unwrap());
         println!("{}", grid);
+        print_share(&grid);
+
+        if let Some(path) = save {
+            let result = library::load(&path).and_then(|mut library| {
+                let mut entry = LibraryEntry::new(&grid)?;
+                entry.generator = Some(GeneratorParams {
+                    size,
+                    blocker_count,
+                    blocker_num_count,
+                    target_difficulty,
+                    symmetric: !not_symmetric,
+                    seed,
+                });
+                if !library.add(entry) {
+                    println!("An equivalent puzzle is already in {}", path.display());
+                }
+                library::save(&path, &library)
+            });
+            if let Err(e) = result {
+                println!("Error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else if let Some(date) = daily_date {
+        let date = if date == "today" {
+            Date::today()
+        } else {
+            match date.parse::<Date>() {
+                Ok(date) => date,
+                Err(e) => {
+                    println!("Error: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            }
+        };
+        info!("Generating the puzzle of {} with difficulty {}", date, target_difficulty);
+        let grid = daily(date, target_difficulty);
+        println!("{}", grid);
+        print_share(&grid);
     } else if solve {
         if let Some(puzzle) = puzzle {
             let mut grid = match Grid::parse(vec![puzzle.clone()]) {
@@ -85,13 +268,65 @@ pub fn cli() -> ExitCode {
                     return ExitCode::FAILURE;
                 }
             };
+            let strats = if no_uniqueness {
+                StrategyList::all().without_uniqueness()
+            } else {
+                StrategyList::all()
+            };
+            if let Some(path) = walkthrough {
+                let format = match path.extension().and_then(|ext| ext.to_str()) {
+                    Some("html" | "htm") => WalkthroughFormat::Html,
+                    _ => WalkthroughFormat::Markdown,
+                };
+                let document = Walkthrough::new(&grid, &strats).render("Str8ts walkthrough", format, options);
+                if let Err(e) = std::fs::write(&path, document) {
+                    println!("Failed to write {}: {}", path.display(), e);
+                    return ExitCode::FAILURE;
+                }
+            }
+
+            print_share(&grid);
+
+            let models = [(cnf, Cnf::new(&grid).to_dimacs()), (minizinc, to_minizinc(&grid))];
+            for (path, model) in models {
+                if let Some(path) = path {
+                    if let Err(e) = std::fs::write(&path, model) {
+                        println!("Failed to write {}: {}", path.display(), e);
+                        return ExitCode::FAILURE;
+                    }
+                }
+            }
+            if let Some(path) = decode {
+                let decoded = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+                    .and_then(|output| {
+                        if output.contains("----------") || output.contains("=====") {
+                            decode_output(&grid, &output)
+                        } else {
+                            decode_assignment(&grid, &output)
+                        }
+                    });
+                return match decoded {
+                    Ok(solution) => {
+                        println!("{}", solution);
+                        ExitCode::SUCCESS
+                    }
+                    Err(e) => {
+                        println!("Error: {}", e);
+                        ExitCode::FAILURE
+                    }
+                };
+            }
+
             info!("Solving puzzle");
             info!("\n{}", grid);
             info!("Steps:");
 
+            let color = std::io::stdout().is_terminal();
             let mut step_count = 0;
             loop {
-                match solve_round(&mut grid, true) {
+                let before = grid.clone();
+                match run_strat(&mut grid, &strats) {
                     Ok(SolveResults {
                         ty: SolveType::PuzzleSolved,
                         meta: _,
@@ -101,9 +336,41 @@ pub fn cli() -> ExitCode {
                     Ok(step) => {
                         step_count += 1;
                         info!("{}: {}", step_count, step);
+                        if explain {
+                            println!("{}: {}", step_count, step.message().format(options));
+                            let colors = &step.meta.colors;
+                            println!(
+                                "{}",
+                                AnsiGrid {
+                                    grid: &before,
+                                    colors,
+                                    color
+                                }
+                            );
+                        }
                     }
                     Err(e) => {
-                        println!("Failed to solve grid: {}", e);
+                        if explain {
+                            let colors = &e.meta.colors;
+                            println!(
+                                "{}",
+                                AnsiGrid {
+                                    grid: &before,
+                                    colors,
+                                    color
+                                }
+                            );
+                        }
+                        println!("Failed to solve grid: {}", e.message().format(options));
+                        if let ValidationError::Ambiguous { cells, solutions } = &e.ty {
+                            for (i, numbers) in solutions.iter().enumerate() {
+                                let mut solution = before.clone();
+                                for (&pos, &n) in cells.iter().zip(numbers) {
+                                    solution.set_cell(pos, Cell::Solution(n));
+                                }
+                                println!("Solution {}:\n{}", i + 1, solution);
+                            }
+                        }
                         println!("Original puzzle: {}", puzzle);
                         return ExitCode::FAILURE;
                     }
@@ -119,6 +386,68 @@ pub fn cli() -> ExitCode {
             let _ = Args::command().print_help();
             return ExitCode::FAILURE;
         }
+    } else if play {
+        let Some(puzzle) = puzzle else {
+            println!("Error: argument puzzle required for playing");
+            let _ = Args::command().print_help();
+            return ExitCode::FAILURE;
+        };
+        let grid = match Grid::parse(vec![puzzle]) {
+            Ok(grid) => grid,
+            Err(e) => {
+                println!("Failed to parse grid: {}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+        let game = match Game::new(&grid) {
+            Ok(game) => game,
+            Err(e) => {
+                println!("Invalid puzzle: {}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+        if let Err(e) = tui::play(game, options) {
+            println!("Terminal error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    } else if let Some(input) = booklet {
+        match booklet::write_booklet(&input, &output, &title, per_page) {
+            Ok(files) => {
+                for file in files {
+                    println!("Wrote {}", file.display());
+                }
+            }
+            Err(e) => {
+                println!("Error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else if let Some(path) = library {
+        let filter = Filter {
+            stars,
+            technique,
+            tag,
+            author: None,
+        };
+        let result = library::load(&path).and_then(|mut library| {
+            if let Some(input) = import {
+                let (added, duplicates) = library::import(&mut library, &input, &tags, author.as_deref())?;
+                library::save(&path, &library)?;
+                println!("Added {} puzzles, skipped {} duplicates", added, duplicates);
+            } else if let Some(output) = export {
+                let count = library::export(&library, &filter, &output)?;
+                println!("Wrote {} puzzles to {}", count, output.display());
+            } else {
+                for line in library::list(&library, &filter) {
+                    println!("{}", line);
+                }
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
+            println!("Error: {}", e);
+            return ExitCode::FAILURE;
+        }
     }
 
     ExitCode::SUCCESS
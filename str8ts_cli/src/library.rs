@@ -0,0 +1,144 @@
+use crate::booklet::parse_puzzles;
+use solver::library::{Filter, Library, LibraryEntry};
+use std::fs;
+use std::path::Path;
+
+fn is_jsonl(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("jsonl"))
+}
+
+pub fn load(path: &Path) -> Result<Library, String> {
+    if !path.exists() {
+        return Ok(Library::default());
+    }
+    let text = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Library::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+pub fn save(path: &Path, library: &Library) -> Result<(), String> {
+    fs::write(path, library.to_jsonl()).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/* Adds the puzzles of a text file (as read by --booklet) or another library. Returns (added, duplicates). */
+pub fn import(
+    library: &mut Library,
+    input: &Path,
+    tags: &[String],
+    author: Option<&str>,
+) -> Result<(usize, usize), String> {
+    let entries = if is_jsonl(input) {
+        load(input)?.into_entries()
+    } else {
+        let text = fs::read_to_string(input).map_err(|e| format!("Failed to read {}: {}", input.display(), e))?;
+        parse_puzzles(&text)?
+            .iter()
+            .enumerate()
+            .map(|(i, grid)| LibraryEntry::new(grid).map_err(|e| format!("Failed to solve puzzle #{}: {}", i + 1, e)))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let mut added = 0;
+    let mut duplicates = 0;
+    for mut entry in entries {
+        for tag in tags {
+            if !entry.has_tag(tag) {
+                entry.tags.push(tag.clone());
+            }
+        }
+        if let Some(author) = author {
+            entry.author = Some(author.to_string());
+        }
+        if library.add(entry) {
+            added += 1;
+        } else {
+            duplicates += 1;
+        }
+    }
+    Ok((added, duplicates))
+}
+
+/* A .jsonl output gets the matching entries as a library, anything else one puzzle string per line */
+pub fn export(library: &Library, filter: &Filter, output: &Path) -> Result<usize, String> {
+    let entries = library.filter(filter).cloned().collect::<Vec<_>>();
+    let count = entries.len();
+    let text = if is_jsonl(output) {
+        Library::new(entries).to_jsonl()
+    } else {
+        entries.iter().map(|entry| format!("{}\n", entry.puzzle)).collect()
+    };
+    fs::write(output, text).map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+    Ok(count)
+}
+
+pub fn list(library: &Library, filter: &Filter) -> Vec<String> {
+    library
+        .entries()
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| filter.matches(entry))
+        .map(|(i, entry)| {
+            let mut line = format!(
+                "#{} {}* {} [{}]",
+                i + 1,
+                entry.stars(),
+                entry.puzzle,
+                entry.difficulty.techniques().join(", ")
+            );
+            if !entry.tags.is_empty() {
+                line += &format!(" tags: {}", entry.tags.join(", "));
+            }
+            if let Some(author) = &entry.author {
+                line += &format!(" by {}", author);
+            }
+            line
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str = "
+12#.7...6
+.#.4..3..
+.#.#i....
+7..3.....
+9#3#.#8#7
+.....1..4
+....h#.#.
+..9..8.#.
+5...4.#32
+";
+
+    #[test]
+    fn test_import_export() {
+        let dir = std::env::temp_dir().join(format!("str8ts-library-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let puzzles = dir.join("puzzles.txt");
+        fs::write(&puzzles, PUZZLE).unwrap();
+
+        let mut library = load(&dir.join("missing.jsonl")).unwrap();
+        let tags = vec!["weekly".to_string()];
+        assert_eq!(import(&mut library, &puzzles, &tags, Some("jgke")).unwrap(), (1, 0));
+        assert_eq!(import(&mut library, &puzzles, &tags, None).unwrap(), (0, 1));
+
+        let path = dir.join("library.jsonl");
+        save(&path, &library).unwrap();
+        assert_eq!(load(&path).unwrap(), library);
+
+        let lines = list(&library, &Filter::default());
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].ends_with("tags: weekly by jgke"));
+
+        let exported = dir.join("export.txt");
+        let filter = Filter {
+            tag: Some("weekly".to_string()),
+            ..Filter::default()
+        };
+        assert_eq!(export(&library, &filter, &exported).unwrap(), 1);
+        let text = fs::read_to_string(&exported).unwrap();
+        assert_eq!(parse_puzzles(&text).unwrap(), vec![library.entries()[0].grid().unwrap()]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
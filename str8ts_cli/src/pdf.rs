@@ -0,0 +1,243 @@
+use crate::booklet::{star_points, Item, Page, PAGE_HEIGHT, PAGE_WIDTH};
+use solver::grid::{Cell, Grid};
+use std::fmt::Write;
+
+/* Minimal PDF writer for booklet pages, using only the standard Helvetica fonts */
+
+const HELVETICA: &str = "/F1";
+const HELVETICA_BOLD: &str = "/F2";
+/* Digits are 0.556 em wide in both Helvetica variants */
+const DIGIT_WIDTH: f64 = 0.556;
+
+/* Non-Latin-1 characters are not available in the standard fonts */
+fn encode_text(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' '..='~' => out.push(c),
+            c if (c as u32) <= 0xff => write!(out, "\\{:03o}", c as u32).unwrap(),
+            _ => out.push('?'),
+        }
+    }
+    out
+}
+
+/* Page coordinates grow downwards like in SVG, PDF coordinates grow upwards */
+fn flip(y: f64) -> f64 {
+    PAGE_HEIGHT as f64 - y
+}
+
+fn text(out: &mut String, x: f64, baseline: f64, size: f64, font: &str, s: &str) {
+    writeln!(
+        out,
+        "BT {} {} Tf {:.2} {:.2} Td ({}) Tj ET",
+        font,
+        size,
+        x,
+        flip(baseline),
+        encode_text(s)
+    )
+    .unwrap();
+}
+
+fn rect(out: &mut String, x: f64, y: f64, width: f64, height: f64, op: &str) {
+    writeln!(out, "{:.2} {:.2} {:.2} {:.2} re {}", x, flip(y + height), width, height, op).unwrap();
+}
+
+fn digit(out: &mut String, center: (f64, f64), size: f64, font: &str, num: u8) {
+    let x = center.0 - DIGIT_WIDTH * size / 2.0;
+    let baseline = center.1 + 0.36 * size;
+    text(out, x, baseline, size, font, &num.to_string());
+}
+
+/* Same appearance as solver::svg::grid_group */
+fn grid(out: &mut String, left: usize, top: usize, cell_size: usize, grid: &Grid, solution: bool) {
+    let (left, top, size) = (left as f64, top as f64, cell_size as f64);
+    let digit_size = (cell_size * 3 / 5) as f64;
+
+    writeln!(out, "1 1 1 rg 0 0 0 RG 2 w").unwrap();
+    rect(out, left + 1.0, top + 1.0, grid.x as f64 * size + 2.0, grid.y as f64 * size + 2.0, "B");
+
+    for ((x, y), cell) in grid.iter_by_cells() {
+        let cx = left + 2.0 + x as f64 * size;
+        let cy = top + 2.0 + y as f64 * size;
+        let center = (cx + size / 2.0, cy + size / 2.0);
+        match cell {
+            Cell::Black | Cell::Blocker(_) => {
+                writeln!(out, "0 0 0 rg").unwrap();
+                rect(out, cx, cy, size, size, "f");
+            }
+            _ => {
+                writeln!(out, "0.533 0.533 0.533 RG 1 w").unwrap();
+                rect(out, cx, cy, size, size, "S");
+            }
+        }
+        match cell {
+            Cell::Blocker(n) => {
+                writeln!(out, "1 1 1 rg").unwrap();
+                digit(out, center, digit_size, HELVETICA_BOLD, n);
+            }
+            Cell::Requirement(n) => {
+                writeln!(out, "0 0 0 rg").unwrap();
+                digit(out, center, digit_size, HELVETICA_BOLD, n);
+            }
+            Cell::Solution(n) if solution => {
+                writeln!(out, "0.102 0.337 0.769 rg").unwrap();
+                digit(out, center, digit_size, HELVETICA, n);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn page_content(page: &Page) -> String {
+    let mut out = String::new();
+    for item in page {
+        match item {
+            Item::Text {
+                x,
+                y,
+                size,
+                bold,
+                text: s,
+            } => {
+                writeln!(out, "0 0 0 rg").unwrap();
+                let font = if *bold { HELVETICA_BOLD } else { HELVETICA };
+                text(&mut out, *x as f64, *y as f64, *size as f64, font, s);
+            }
+            Item::Star { x, y, radius, filled } => {
+                writeln!(out, "0 0 0 rg 0 0 0 RG 0.8 w").unwrap();
+                for (i, (px, py)) in star_points(*x, *y, *radius).into_iter().enumerate() {
+                    writeln!(out, "{:.2} {:.2} {}", px, flip(py), if i == 0 { "m" } else { "l" }).unwrap();
+                }
+                writeln!(out, "{}", if *filled { "b" } else { "s" }).unwrap();
+            }
+            Item::Grid {
+                x,
+                y,
+                cell_size,
+                grid: g,
+                solution,
+            } => grid(&mut out, *x, *y, *cell_size, g, *solution),
+        }
+    }
+    out
+}
+
+pub fn write_pdf(pages: &[Page]) -> Vec<u8> {
+    /* 1: catalog, 2: page tree, 3-4: fonts, then a page object and a content stream for each page */
+    let page_ids = (0..pages.len()).map(|i| 5 + 2 * i).collect::<Vec<_>>();
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            page_ids
+                .iter()
+                .map(|id| format!("{} 0 R", id))
+                .collect::<Vec<_>>()
+                .join(" "),
+            pages.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>".to_string(),
+    ];
+    for (page, id) in pages.iter().zip(&page_ids) {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << {} 3 0 R {} 4 0 R >> >> /Contents {} 0 R >>",
+            PAGE_WIDTH,
+            PAGE_HEIGHT,
+            HELVETICA,
+            HELVETICA_BOLD,
+            id + 1
+        ));
+        let content = page_content(page);
+        objects.push(format!("<< /Length {} >>\nstream\n{}endstream", content.len(), content));
+    }
+
+    let mut out = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::new();
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        writeln!(out, "{} 0 obj\n{}\nendobj", i + 1, object).unwrap();
+    }
+    let xref = out.len();
+    writeln!(out, "xref\n0 {}\n0000000000 65535 f ", objects.len() + 1).unwrap();
+    for offset in offsets {
+        writeln!(out, "{:010} 00000 n ", offset).unwrap();
+    }
+    writeln!(out, "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF", objects.len() + 1, xref).unwrap();
+    out.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_text() {
+        assert_eq!(encode_text("Str8ts (1)"), "Str8ts \\(1\\)");
+        assert_eq!(encode_text("a\\b"), "a\\\\b");
+        assert_eq!(encode_text("Päivä"), "P\\344iv\\344");
+        assert_eq!(encode_text("数独"), "??");
+    }
+
+    #[test]
+    fn test_grid() {
+        let mut puzzle = Grid::parse_oneline("a1\n2.").unwrap();
+        let mut out = String::new();
+        grid(&mut out, 0, 0, 10, &puzzle, false);
+        /* a black square for the blocker, and the given numbers in white and black */
+        assert_eq!(out.matches(" re f").count(), 1);
+        assert!(out.contains("1 1 1 rg\nBT /F2 6 Tf"));
+        assert!(out.contains("0 0 0 rg\nBT /F2 6 Tf"));
+        assert!(!out.contains("/F1"));
+
+        puzzle.set_cell((1, 1), Cell::Solution(1));
+        let mut solved = String::new();
+        grid(&mut solved, 0, 0, 10, &puzzle, true);
+        assert!(solved.contains("0.102 0.337 0.769 rg\nBT /F1 6 Tf"));
+    }
+
+    #[test]
+    fn test_write_pdf() {
+        let pages = vec![
+            vec![Item::Text {
+                x: 10,
+                y: 20,
+                size: 12,
+                bold: false,
+                text: "First".to_string(),
+            }],
+            vec![Item::Star {
+                x: 10,
+                y: 20,
+                radius: 5,
+                filled: true,
+            }],
+        ];
+        let pdf = String::from_utf8(write_pdf(&pages)).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("/Kids [5 0 R 7 0 R] /Count 2"));
+        assert!(pdf.contains("BT /F1 12 Tf 10.00 822.00 Td (First) Tj ET"));
+
+        /* every object starts at the offset given in the cross-reference table */
+        let xref = pdf.rsplit("startxref\n").next().unwrap().lines().next().unwrap();
+        let table = &pdf[xref.parse::<usize>().unwrap()..];
+        assert!(table.starts_with("xref\n0 9\n"));
+        for (i, line) in table.lines().skip(3).take(8).enumerate() {
+            let offset = line[..10].parse::<usize>().unwrap();
+            assert!(pdf[offset..].starts_with(&format!("{} 0 obj\n", i + 1)));
+        }
+
+        /* the stream lengths match the content streams */
+        for stream in pdf.split("<< /Length ").skip(1) {
+            let (length, rest) = stream.split_once(" >>\nstream\n").unwrap();
+            assert_eq!(rest.find("endstream"), Some(length.parse::<usize>().unwrap()));
+        }
+    }
+}
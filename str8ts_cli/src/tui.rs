@@ -0,0 +1,388 @@
+use crate::ansi::HIGHLIGHT_COLORS;
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use solver::coordinate::Coordinate;
+use solver::game::{Game, Hint};
+use solver::grid::{Cell, Grid, Point};
+use solver::messages::MessageOptions;
+use solver::solve_result::{highlight, SolveType};
+use solver::solver::solve_round;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+const HELP: &str = "arrows: move  1-9: enter  m: marks  c: clear  u/r: undo/redo  a: prune marks  h: hint  s: step solver  esc: leave solver  q: quit";
+
+struct App {
+    game: Game,
+    cursor: Point,
+    mark_mode: bool,
+    /* Grid being stepped through with the solver, shown instead of the game when present */
+    solver: Option<Grid>,
+    colors: Vec<Vec<(Point, u8)>>,
+    message: String,
+    options: MessageOptions,
+    quit: bool,
+}
+
+/* Restores the terminal even if drawing fails halfway */
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn new() -> io::Result<TerminalGuard> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(TerminalGuard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), ResetColor, Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+impl App {
+    fn new(mut game: Game, options: MessageOptions) -> App {
+        /* cache the solution up front so checking for mistakes stays cheap */
+        let message = match game.solution() {
+            Ok(_) => String::new(),
+            Err(e) => format!("Warning: puzzle cannot be solved: {}", e.message().format(options)),
+        };
+        App {
+            game,
+            cursor: (0, 0),
+            mark_mode: false,
+            solver: None,
+            colors: Vec::new(),
+            message,
+            options,
+            quit: false,
+        }
+    }
+
+    fn move_cursor(&mut self, dx: isize, dy: isize) {
+        let size = self.game.puzzle.x as isize;
+        let x = (self.cursor.0 as isize + dx).rem_euclid(size);
+        let y = (self.cursor.1 as isize + dy).rem_euclid(size);
+        self.cursor = (x as usize, y as usize);
+    }
+
+    fn enter_number(&mut self, num: u8) {
+        let changed = if self.mark_mode {
+            self.game.toggle_mark(self.cursor, num)
+        } else {
+            self.game.set_number(self.cursor, num)
+        };
+        if changed {
+            self.leave_solver();
+            if self.game.is_solved() {
+                self.message = format!("Solved in {} moves!", self.game.move_count);
+            }
+        }
+    }
+
+    fn clear_cell(&mut self) {
+        if self.game.clear_cell(self.cursor) {
+            self.leave_solver();
+        }
+    }
+
+    fn hint(&mut self) {
+        self.leave_solver();
+        match self.game.apply_hint() {
+            Ok(Some(Hint { pos, num, steps })) => {
+                let last = steps.last().unwrap();
+                self.message = format!(
+                    "Hint: {} is {}. {}",
+                    Coordinate(pos).format(self.options.notation),
+                    num,
+                    last.message().format(self.options)
+                );
+                self.colors = last.meta.colors.clone();
+                self.colors.push(vec![(pos, num)]);
+                self.cursor = pos;
+            }
+            Ok(None) => self.message = "Nothing left to hint".to_string(),
+            Err(e) => {
+                self.message = format!("Cannot hint: {}", e.message().format(self.options));
+                self.colors = e.meta.colors;
+            }
+        }
+    }
+
+    fn step_solver(&mut self) {
+        let grid = self.solver.get_or_insert_with(|| self.game.grid());
+        match solve_round(grid, true) {
+            Ok(step) => {
+                let message = step.message().format(self.options);
+                self.message = if step.ty == SolveType::PuzzleSolved {
+                    message
+                } else {
+                    format!("Solver: {}", message)
+                };
+                self.colors = step.meta.colors;
+            }
+            Err(e) => {
+                self.message = format!("Solver: {}", e.message().format(self.options));
+                self.colors = e.meta.colors;
+            }
+        }
+    }
+
+    fn leave_solver(&mut self) {
+        self.solver = None;
+        self.colors.clear();
+        self.message.clear();
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Left => self.move_cursor(-1, 0),
+            KeyCode::Right => self.move_cursor(1, 0),
+            KeyCode::Up => self.move_cursor(0, -1),
+            KeyCode::Down => self.move_cursor(0, 1),
+            KeyCode::Char(c @ '1'..='9') => self.enter_number(c as u8 - b'0'),
+            KeyCode::Char('m') => self.mark_mode = !self.mark_mode,
+            KeyCode::Char('c') | KeyCode::Char('0') | KeyCode::Backspace | KeyCode::Delete => self.clear_cell(),
+            KeyCode::Char('u') => {
+                self.leave_solver();
+                self.game.undo();
+            }
+            KeyCode::Char('r') => {
+                self.leave_solver();
+                self.game.redo();
+            }
+            KeyCode::Char('a') => {
+                self.leave_solver();
+                self.game.auto_prune_marks();
+            }
+            KeyCode::Char('h') => self.hint(),
+            KeyCode::Char('s') => self.step_solver(),
+            KeyCode::Esc => self.leave_solver(),
+            _ => {}
+        }
+    }
+
+    fn highlight(&self, pos: Point, num: u8) -> Option<Color> {
+        highlight(&self.colors, &HIGHLIGHT_COLORS, pos, num)
+    }
+
+    /* The cell as shown on screen: solver candidates in solver mode, player marks otherwise */
+    fn shown_cell(&self, pos: Point) -> Cell {
+        if let Some(grid) = &self.solver {
+            return grid.get_cell(pos).clone();
+        }
+        match self.game.puzzle.get_cell(pos) {
+            Cell::Indeterminate(_) => match self.game.entry(pos) {
+                Some(n) => Cell::Solution(n),
+                None => Cell::Indeterminate(self.game.marks(pos)),
+            },
+            other => other.clone(),
+        }
+    }
+
+    fn draw_cell_row(&self, out: &mut impl Write, pos: Point, row: u8) -> io::Result<()> {
+        let cell = self.shown_cell(pos);
+        let cursor = pos == self.cursor;
+        for col in 0..3 {
+            let num = row * 3 + col + 1;
+            let (text, fg, bg, bold) = match cell {
+                Cell::Black => (' ', Color::White, Some(Color::DarkGrey), false),
+                Cell::Blocker(n) => {
+                    let c = if num == 5 { (b'0' + n) as char } else { ' ' };
+                    (c, Color::White, Some(Color::DarkGrey), true)
+                }
+                Cell::Requirement(n) => (if num == 5 { (b'0' + n) as char } else { ' ' }, Color::Reset, None, true),
+                Cell::Solution(n) => {
+                    let bg = if num == 5 { self.highlight(pos, n) } else { None };
+                    (if num == 5 { (b'0' + n) as char } else { ' ' }, Color::Cyan, bg, true)
+                }
+                Cell::Indeterminate(set) => {
+                    if set.contains(num) && (num as usize) <= self.game.puzzle.x {
+                        ((b'0' + num) as char, Color::DarkYellow, self.highlight(pos, num), false)
+                    } else {
+                        (' ', Color::Reset, None, false)
+                    }
+                }
+            };
+            queue!(out, SetForegroundColor(fg))?;
+            if let Some(bg) = bg {
+                queue!(out, SetBackgroundColor(bg))?;
+            }
+            if bold {
+                queue!(out, SetAttribute(Attribute::Bold))?;
+            }
+            if cursor {
+                queue!(out, SetAttribute(Attribute::Reverse))?;
+            }
+            queue!(out, Print(text), SetAttribute(Attribute::Reset), ResetColor)?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        queue!(out, MoveTo(0, 0), Clear(ClearType::All))?;
+        let size = self.game.puzzle.x;
+        let mut line = 0;
+        for y in 0..size {
+            for row in 0..3 {
+                queue!(out, MoveTo(0, line))?;
+                for x in 0..size {
+                    self.draw_cell_row(out, (x, y), row)?;
+                    queue!(out, Print(' '))?;
+                }
+                line += 1;
+            }
+            line += 1;
+        }
+
+        let mode = match (&self.solver, self.mark_mode) {
+            (Some(_), _) => "solver",
+            (None, true) => "marks",
+            (None, false) => "numbers",
+        };
+        let elapsed = self.game.elapsed.as_secs();
+        let status = format!(
+            "Mode: {}  Cell: {}  Moves: {}  Time: {}:{:02}",
+            mode,
+            Coordinate(self.cursor).format(self.options.notation),
+            self.game.move_count,
+            elapsed / 60,
+            elapsed % 60
+        );
+        queue!(out, MoveTo(0, line), Print(status))?;
+        queue!(out, MoveTo(0, line + 1), Print(&self.message))?;
+        queue!(out, MoveTo(0, line + 3), Print(HELP))?;
+        out.flush()
+    }
+}
+
+pub fn play(game: Game, options: MessageOptions) -> io::Result<()> {
+    let mut app = App::new(game, options);
+    let _guard = TerminalGuard::new()?;
+    let mut stdout = io::stdout();
+    let mut last_tick = Instant::now();
+
+    while !app.quit {
+        app.draw(&mut stdout)?;
+        if event::poll(Duration::from_millis(500))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    app.handle_key(key);
+                }
+            }
+        }
+        let now = Instant::now();
+        if !app.game.is_solved() {
+            app.game.tick(now - last_tick);
+        }
+        last_tick = now;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str = "
+12#.7...6
+.#.4..3..
+.#.#i....
+7..3.....
+9#3#.#8#7
+.....1..4
+....h#.#.
+..9..8.#.
+5...4.#32
+";
+
+    fn app() -> App {
+        App::new(Game::new(&Grid::parse_oneline(PUZZLE).unwrap()).unwrap(), MessageOptions::default())
+    }
+
+    fn press(app: &mut App, code: KeyCode) {
+        app.handle_key(KeyEvent::from(code));
+    }
+
+    #[test]
+    fn test_keys() {
+        let mut app = app();
+        assert_eq!(app.message, "");
+
+        /* the cursor wraps around the edges */
+        press(&mut app, KeyCode::Left);
+        assert_eq!(app.cursor, (8, 0));
+        press(&mut app, KeyCode::Up);
+        assert_eq!(app.cursor, (8, 8));
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Right);
+        assert_eq!(app.cursor, (0, 0));
+
+        /* givens can't be changed */
+        press(&mut app, KeyCode::Char('5'));
+        assert_eq!(app.game.move_count, 0);
+
+        for _ in 0..3 {
+            press(&mut app, KeyCode::Right);
+        }
+        press(&mut app, KeyCode::Char('5'));
+        assert_eq!(app.game.entry((3, 0)), Some(5));
+        press(&mut app, KeyCode::Char('u'));
+        assert_eq!(app.game.entry((3, 0)), None);
+        press(&mut app, KeyCode::Char('r'));
+        assert_eq!(app.game.entry((3, 0)), Some(5));
+        press(&mut app, KeyCode::Backspace);
+        assert_eq!(app.game.entry((3, 0)), None);
+
+        press(&mut app, KeyCode::Char('m'));
+        assert!(app.mark_mode);
+        press(&mut app, KeyCode::Char('4'));
+        press(&mut app, KeyCode::Char('8'));
+        press(&mut app, KeyCode::Char('4'));
+        assert_eq!(app.game.entry((3, 0)), None);
+        assert_eq!(app.game.marks((3, 0)).into_iter().collect::<Vec<_>>(), vec![8]);
+
+        press(&mut app, KeyCode::Char('q'));
+        assert!(app.quit);
+    }
+
+    #[test]
+    fn test_hint_and_solver() {
+        let mut app = app();
+        press(&mut app, KeyCode::Char('h'));
+        assert!(app.message.starts_with("Hint: "), "{}", app.message);
+        let n = app.game.entry(app.cursor).unwrap();
+        assert_eq!(app.colors.last(), Some(&vec![(app.cursor, n)]));
+        assert_eq!(app.highlight(app.cursor, n), Some(HIGHLIGHT_COLORS[(app.colors.len() - 1) % 4]));
+
+        press(&mut app, KeyCode::Char('s'));
+        assert!(app.solver.is_some());
+        assert!(app.message.starts_with("Solver: "), "{}", app.message);
+        press(&mut app, KeyCode::Esc);
+        assert!(app.solver.is_none());
+        assert!(app.colors.is_empty());
+        assert_eq!(app.message, "");
+
+        /* undoing a move leaves the solver as well */
+        press(&mut app, KeyCode::Char('s'));
+        press(&mut app, KeyCode::Char('u'));
+        assert!(app.solver.is_none());
+        assert_eq!(app.game.entry(app.cursor), None);
+    }
+
+    #[test]
+    fn test_ambiguous_puzzle() {
+        let app = App::new(
+            Game::new(&Grid::parse_oneline("#4..\n..#.\n.#..\n..4#").unwrap()).unwrap(),
+            MessageOptions::default(),
+        );
+        assert!(app.message.starts_with("Warning: "), "{}", app.message);
+    }
+}